@@ -0,0 +1,12 @@
+use day10::{Grid, PipeLoop};
+
+fn main() {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+
+    let grid = Grid::parse(&input).unwrap();
+    let pipe_loop = PipeLoop::trace(&grid).unwrap();
+    print!("{}", pipe_loop.render(&grid));
+}
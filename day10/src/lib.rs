@@ -0,0 +1,366 @@
+use anyhow::Context;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Tile {
+    Start,
+    Ground,
+    Pipe(Connection),
+}
+
+impl Tile {
+    pub fn from_char(char: char) -> anyhow::Result<Self> {
+        match char {
+            'S' => Ok(Self::Start),
+            '.' => Ok(Self::Ground),
+            '|' => Ok(Self::Pipe(Connection(Direction::North, Direction::South))),
+            '-' => Ok(Self::Pipe(Connection(Direction::East, Direction::West))),
+            'L' => Ok(Self::Pipe(Connection(Direction::North, Direction::East))),
+            'J' => Ok(Self::Pipe(Connection(Direction::North, Direction::West))),
+            '7' => Ok(Self::Pipe(Connection(Direction::South, Direction::West))),
+            'F' => Ok(Self::Pipe(Connection(Direction::South, Direction::East))),
+            _ => Err(anyhow::anyhow!("Unknown tile character '{char}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn dx(&self) -> isize {
+        match self {
+            Direction::North => 0,
+            Direction::East => 1,
+            Direction::South => 0,
+            Direction::West => -1,
+        }
+    }
+
+    pub fn dy(&self) -> isize {
+        match self {
+            Direction::North => -1,
+            Direction::East => 0,
+            Direction::South => 1,
+            Direction::West => 0,
+        }
+    }
+
+    pub fn delta(&self) -> (isize, isize) {
+        (self.dx(), self.dy())
+    }
+
+    pub fn reverse(&self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Connection(pub Direction, pub Direction);
+
+impl Connection {
+    pub fn next(&self, last_dir: Direction) -> anyhow::Result<Direction> {
+        if last_dir == self.0 {
+            Ok(self.1)
+        } else if last_dir == self.1 {
+            Ok(self.0)
+        } else {
+            anyhow::bail!("Invalid last direction {last_dir:?}");
+        }
+    }
+
+    pub fn connects(&self, dir: Direction) -> bool {
+        self.0 == dir || self.1 == dir
+    }
+
+    pub fn box_char(&self) -> char {
+        use Direction::*;
+        match (
+            self.connects(North),
+            self.connects(East),
+            self.connects(South),
+        ) {
+            (true, _, true) => '│',
+            (true, true, _) => '└',
+            (true, _, _) => '┘',
+            (_, true, true) => '┌',
+            (_, true, _) => '─',
+            _ => '┐',
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<Vec<Tile>>,
+}
+
+impl Grid {
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        let tiles = input
+            .lines()
+            .map(|line| line.chars().map(Tile::from_char).collect())
+            .collect::<Result<Vec<Vec<_>>, _>>()?;
+
+        let height = tiles.len();
+        let width = tiles
+            .first()
+            .with_context(|| "Grid must not be empty")?
+            .len();
+
+        Ok(Grid {
+            width,
+            height,
+            tiles,
+        })
+    }
+
+    pub fn starting_pos(&self) -> anyhow::Result<(isize, isize)> {
+        for x in 0..self.width as isize {
+            for y in 0..self.height as isize {
+                if matches!(self.get(x, y), Some(Tile::Start)) {
+                    return Ok((x, y));
+                }
+            }
+        }
+
+        anyhow::bail!("Starting position not found");
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<Tile> {
+        if x >= 0 && y >= 0 {
+            self.tiles.get(y as usize)?.get(x as usize).copied()
+        } else {
+            None
+        }
+    }
+
+    pub fn relative(&self, dx: isize, dy: isize, x: isize, y: isize) -> Option<Tile> {
+        self.get(x + dx, y + dy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Region {
+    Loop,
+    Inside,
+    Outside,
+}
+
+#[derive(Debug, Clone)]
+pub struct PipeLoop {
+    start_pipe: Connection,
+    path: Vec<(isize, isize)>,
+}
+
+impl PipeLoop {
+    /// Finds the loop going through `S`, inferring which pipe is hidden under it.
+    pub fn trace(grid: &Grid) -> anyhow::Result<Self> {
+        let (starting_x, starting_y) = grid.starting_pos()?;
+
+        for dir in Direction::ALL {
+            let (dx, dy) = dir.delta();
+            let Some(Tile::Pipe(conn)) = grid.relative(dx, dy, starting_x, starting_y) else {
+                continue;
+            };
+
+            if !conn.connects(dir.reverse()) {
+                continue;
+            }
+
+            // Stray pipes can point at `S` too, so only accept a direction that comes back around
+            if let Ok((path, last_dir)) = Self::follow(grid, (starting_x, starting_y), dir) {
+                return Ok(PipeLoop {
+                    start_pipe: Connection(dir, last_dir.reverse()),
+                    path,
+                });
+            }
+        }
+
+        anyhow::bail!("No loop going through the starting position found");
+    }
+
+    fn follow(
+        grid: &Grid,
+        (starting_x, starting_y): (isize, isize),
+        first_dir: Direction,
+    ) -> anyhow::Result<(Vec<(isize, isize)>, Direction)> {
+        let mut last_dir = first_dir;
+        let (mut current_x, mut current_y) =
+            (starting_x + last_dir.dx(), starting_y + last_dir.dy());
+        let mut path = vec![(starting_x, starting_y)];
+
+        loop {
+            let tile = grid.get(current_x, current_y);
+            match tile {
+                Some(Tile::Pipe(conn)) => {
+                    path.push((current_x, current_y));
+                    let dir = conn.next(last_dir.reverse())?;
+                    current_x += dir.dx();
+                    current_y += dir.dy();
+                    last_dir = dir;
+                }
+                Some(Tile::Start) => return Ok((path, last_dir)),
+                _ => anyhow::bail!("Encountered invalid tile: {tile:?}"),
+            }
+        }
+    }
+
+    pub fn start(&self) -> (isize, isize) {
+        self.path[0]
+    }
+
+    pub fn start_pipe(&self) -> Connection {
+        self.start_pipe
+    }
+
+    /// Tiles of the loop, beginning at `S` and following the first direction of `start_pipe`.
+    pub fn path(&self) -> &[(isize, isize)] {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.path.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Walks the loop from `S` both ways at once and returns the tile where the two walks meet,
+    /// along with the number of steps it took to get there.
+    pub fn farthest(&self) -> ((isize, isize), usize) {
+        let forwards = self.path.iter().skip(1);
+        let backwards = self.path.iter().skip(1).rev();
+
+        for (steps, (a, b)) in forwards.zip(backwards).enumerate() {
+            if a == b {
+                return (*a, steps + 1);
+            }
+        }
+
+        unreachable!("A loop on a grid always has an even length");
+    }
+
+    pub fn regions(&self, grid: &Grid) -> Vec<Vec<Region>> {
+        let mut regions = vec![vec![Region::Outside; grid.width]; grid.height];
+        let mut pipes = vec![vec![None; grid.width]; grid.height];
+
+        for &(x, y) in &self.path {
+            let pipe = match grid.get(x, y) {
+                Some(Tile::Pipe(conn)) => conn,
+                _ => self.start_pipe,
+            };
+            pipes[y as usize][x as usize] = Some(pipe);
+        }
+
+        // Scan each row and flip sides every time a pipe heading north is crossed
+        for (y, row) in pipes.iter().enumerate() {
+            let mut inside = false;
+
+            for (x, pipe) in row.iter().enumerate() {
+                regions[y][x] = match pipe {
+                    Some(conn) => {
+                        if conn.connects(Direction::North) {
+                            inside = !inside;
+                        }
+                        Region::Loop
+                    }
+                    None if inside => Region::Inside,
+                    None => Region::Outside,
+                };
+            }
+        }
+
+        regions
+    }
+
+    pub fn enclosed(&self, grid: &Grid) -> usize {
+        self.regions(grid)
+            .iter()
+            .flatten()
+            .filter(|region| **region == Region::Inside)
+            .count()
+    }
+
+    /// Draws the map using box-drawing characters, with the loop in bold and the tiles inside and
+    /// outside of it coloured using ANSI escape codes.
+    pub fn render(&self, grid: &Grid) -> String {
+        let regions = self.regions(grid);
+        let mut output = String::new();
+
+        for (y, row) in regions.iter().enumerate() {
+            for (x, region) in row.iter().enumerate() {
+                let char = match grid.get(x as isize, y as isize) {
+                    Some(Tile::Pipe(conn)) => conn.box_char(),
+                    Some(Tile::Start) => self.start_pipe.box_char(),
+                    _ => '·',
+                };
+
+                let style = match region {
+                    Region::Loop if (x as isize, y as isize) == self.start() => "1;33",
+                    Region::Loop => "1",
+                    Region::Inside => "32",
+                    Region::Outside => "2;34",
+                };
+
+                output.push_str(&format!("\x1b[{style}m{char}\x1b[0m"));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn farthest() {
+        let grid = Grid::parse("7-F7-\n.FJ|7\nSJLL7\n|F--J\nLJ.LJ\n").unwrap();
+        let pipe_loop = PipeLoop::trace(&grid).unwrap();
+        assert_eq!(pipe_loop.farthest(), ((4, 2), 8));
+        assert!(matches!(
+            pipe_loop.start_pipe(),
+            Connection(Direction::East, Direction::South)
+        ));
+    }
+
+    #[test]
+    fn enclosed() {
+        let input = "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+";
+        let grid = Grid::parse(input).unwrap();
+        let pipe_loop = PipeLoop::trace(&grid).unwrap();
+        assert_eq!(pipe_loop.enclosed(&grid), 4);
+    }
+}
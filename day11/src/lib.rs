@@ -0,0 +1,135 @@
+#[derive(Debug)]
+pub struct Map {
+    pub width: usize,
+    pub height: usize,
+    galaxies: Vec<(usize, usize)>,
+}
+
+impl Map {
+    pub fn parse(input: &str) -> Self {
+        let width = input.lines().next().expect("Line to not be empty").len();
+        let height = input.lines().count();
+
+        let galaxies = input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, char)| *char == '#')
+                    .map(move |(x, _)| (x, y))
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            width,
+            height,
+            galaxies,
+        }
+    }
+
+    /// Replaces every empty row and column with `factor` of them.
+    pub fn scale(self, factor: usize) -> Self {
+        assert!(factor > 0, "Expansion factor must be at least 1");
+
+        let mut empty_columns = vec![true; self.width];
+        let mut empty_rows = vec![true; self.height];
+        for (x, y) in &self.galaxies {
+            empty_columns[*x] = false;
+            empty_rows[*y] = false;
+        }
+
+        let column_offsets = offsets(&empty_columns, factor - 1);
+        let row_offsets = offsets(&empty_rows, factor - 1);
+
+        let galaxies = self
+            .galaxies
+            .iter()
+            .map(|(x, y)| (x + column_offsets[*x], y + row_offsets[*y]))
+            .collect();
+
+        Self {
+            width: self.width + column_offsets.last().copied().unwrap_or(0),
+            height: self.height + row_offsets.last().copied().unwrap_or(0),
+            galaxies,
+        }
+    }
+
+    /// Galaxies in reading order, so galaxy number `n` is at index `n - 1`.
+    pub fn galaxies(&self) -> &[(usize, usize)] {
+        &self.galaxies
+    }
+
+    /// Distance between two galaxies, numbered from 1 like in the puzzle.
+    pub fn distance(&self, a: usize, b: usize) -> Option<usize> {
+        let (x1, y1) = self.galaxies.get(a.checked_sub(1)?)?;
+        let (x2, y2) = self.galaxies.get(b.checked_sub(1)?)?;
+        Some(x1.abs_diff(*x2) + y1.abs_diff(*y2))
+    }
+
+    /// Sum of the distances between every pair of galaxies.
+    pub fn distance_sum(&self) -> usize {
+        let xs = self.galaxies.iter().map(|(x, _)| *x).collect();
+        let ys = self.galaxies.iter().map(|(_, y)| *y).collect();
+        axis_distance_sum(xs) + axis_distance_sum(ys)
+    }
+}
+
+fn offsets(empty: &[bool], extra: usize) -> Vec<usize> {
+    let mut offset = 0;
+    let mut offsets = Vec::with_capacity(empty.len() + 1);
+
+    for is_empty in empty {
+        offsets.push(offset);
+        if *is_empty {
+            offset += extra;
+        }
+    }
+
+    offsets.push(offset);
+    offsets
+}
+
+fn axis_distance_sum(mut coords: Vec<usize>) -> usize {
+    coords.sort_unstable();
+
+    // Once sorted, each coordinate is at least as large as all the ones before it
+    let mut prefix = 0;
+    let mut sum = 0;
+    for (i, coord) in coords.iter().enumerate() {
+        sum += coord * i - prefix;
+        prefix += coord;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+";
+
+    #[test]
+    fn distances() {
+        let map = Map::parse(EXAMPLE).scale(2);
+        assert_eq!(map.distance(5, 9), Some(9));
+        assert_eq!(map.distance(1, 7), Some(15));
+        assert_eq!(map.distance(8, 9), Some(5));
+        assert_eq!(map.distance(0, 1), None);
+        assert_eq!(map.distance_sum(), 374);
+
+        assert_eq!(Map::parse(EXAMPLE).scale(10).distance_sum(), 1030);
+        assert_eq!(Map::parse(EXAMPLE).scale(100).distance_sum(), 8410);
+    }
+}
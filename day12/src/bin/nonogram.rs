@@ -0,0 +1,19 @@
+use day12::nonogram::Nonogram;
+
+fn main() {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./nonogram.txt").to_string(),
+    };
+
+    let nonogram = Nonogram::parse(&input).unwrap();
+    match nonogram.solve() {
+        Some(solution) => {
+            print!("{}", solution.picture);
+            if !solution.unique {
+                println!("Solution is not unique");
+            }
+        }
+        None => println!("No solution"),
+    }
+}
@@ -0,0 +1,305 @@
+pub mod nonogram;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+impl Condition {
+    pub fn from_char(char: char) -> Self {
+        match char {
+            '.' => Condition::Operational,
+            '#' => Condition::Damaged,
+            '?' => Condition::Unknown,
+            _ => panic!("Unknown condition char '{char}'"),
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Condition::Operational => '.',
+            Condition::Damaged => '#',
+            Condition::Unknown => '?',
+        }
+    }
+}
+
+pub fn format_conditions(conditions: &[Condition]) -> String {
+    conditions.iter().map(|c| c.to_char()).collect()
+}
+
+/// Sizes of the runs of damaged springs in a fully specified row, or `None` if the row still has
+/// unknowns in it.
+pub fn damaged_groups(conditions: &[Condition]) -> Option<Vec<usize>> {
+    let mut groups = vec![];
+    let mut current = 0;
+
+    for condition in conditions {
+        match condition {
+            Condition::Damaged => current += 1,
+            Condition::Operational if current > 0 => {
+                groups.push(current);
+                current = 0;
+            }
+            Condition::Operational => {}
+            Condition::Unknown => return None,
+        }
+    }
+
+    if current > 0 {
+        groups.push(current);
+    }
+    Some(groups)
+}
+
+/// Number of ways `groups` can be placed over `conditions`. Zero means the line is contradictory.
+pub fn count_arrangements(conditions: &[Condition], groups: &[usize]) -> u64 {
+    ArrangementTable::new(conditions, groups).count()
+}
+
+#[derive(Debug, Clone)]
+pub struct ConditionRecord {
+    pub conditions: Vec<Condition>,
+    pub damaged_groups: Vec<usize>,
+}
+
+impl ConditionRecord {
+    pub fn parse(line: &str) -> Self {
+        let mut parts = line.split(' ');
+        let conditions = parts
+            .next()
+            .unwrap()
+            .chars()
+            .map(Condition::from_char)
+            .collect();
+
+        let damaged_groups = parts
+            .next()
+            .unwrap()
+            .split(',')
+            .map(|n| n.parse::<usize>().unwrap())
+            .collect();
+
+        Self {
+            conditions,
+            damaged_groups,
+        }
+    }
+
+    /// Repeats the record `factor` times, joining the copies of the conditions with an unknown.
+    pub fn unfold(self, factor: usize) -> Self {
+        let mut conditions = Vec::with_capacity((self.conditions.len() + 1) * factor);
+        let mut damaged_groups = Vec::with_capacity(self.damaged_groups.len() * factor);
+
+        for i in 0..factor {
+            if i != 0 {
+                conditions.push(Condition::Unknown);
+            }
+
+            conditions.extend_from_slice(&self.conditions);
+            damaged_groups.extend_from_slice(&self.damaged_groups);
+        }
+
+        Self {
+            conditions,
+            damaged_groups,
+        }
+    }
+
+    pub fn count_arrangements(&self) -> u64 {
+        count_arrangements(&self.conditions, &self.damaged_groups)
+    }
+
+    /// Checks whether a fully specified row agrees with the known conditions of this record and
+    /// has the right damaged groups.
+    pub fn matches(&self, row: &[Condition]) -> bool {
+        row.len() == self.conditions.len()
+            && self
+                .conditions
+                .iter()
+                .zip(row)
+                .all(|(known, actual)| *known == Condition::Unknown || known == actual)
+            && damaged_groups(row).is_some_and(|groups| groups == self.damaged_groups)
+    }
+
+    /// Lazily goes through every arrangement, starting with the one that has its damaged springs
+    /// pushed furthest to the right.
+    pub fn arrangements(&self) -> Arrangements {
+        let table = ArrangementTable::new(&self.conditions, &self.damaged_groups);
+        let count = table.count();
+        Arrangements {
+            table,
+            index: 0,
+            count,
+            step: 1,
+        }
+    }
+
+    /// Like `arrangements`, but only yields up to `limit` of them, spread evenly over all of the
+    /// possible arrangements. Useful for records that have too many to go through.
+    pub fn sample(&self, limit: u64) -> Arrangements {
+        let mut arrangements = self.arrangements();
+        if limit == 0 {
+            arrangements.count = 0;
+        } else {
+            arrangements.step = arrangements.count.div_ceil(limit).max(1);
+        }
+        arrangements
+    }
+}
+
+/// `ways[i][j]` is the number of ways to fit `groups[j..]` into `conditions[i..]`.
+#[derive(Debug, Clone)]
+struct ArrangementTable {
+    conditions: Vec<Condition>,
+    groups: Vec<usize>,
+    ways: Vec<u64>,
+}
+
+impl ArrangementTable {
+    fn new(conditions: &[Condition], groups: &[usize]) -> Self {
+        let len = conditions.len();
+        let columns = groups.len() + 1;
+        let mut ways = vec![0; (len + 1) * columns];
+
+        // Index of the next operational spring at or after each position
+        let mut next_operational = vec![len; len + 1];
+        for i in (0..len).rev() {
+            next_operational[i] = if conditions[i] == Condition::Operational {
+                i
+            } else {
+                next_operational[i + 1]
+            };
+        }
+
+        ways[len * columns + groups.len()] = 1;
+        for i in (0..len).rev() {
+            for j in 0..columns {
+                let mut count = 0;
+
+                if conditions[i] != Condition::Damaged {
+                    count += ways[(i + 1) * columns + j];
+                }
+
+                if j < groups.len() && conditions[i] != Condition::Operational {
+                    let end = i + groups[j];
+                    let fits = end <= len
+                        && next_operational[i] >= end
+                        && conditions.get(end) != Some(&Condition::Damaged);
+
+                    if fits {
+                        let next = (end + 1).min(len);
+                        count += ways[next * columns + j + 1];
+                    }
+                }
+
+                ways[i * columns + j] = count;
+            }
+        }
+
+        Self {
+            conditions: conditions.to_vec(),
+            groups: groups.to_vec(),
+            ways,
+        }
+    }
+
+    fn ways(&self, i: usize, j: usize) -> u64 {
+        self.ways[i * (self.groups.len() + 1) + j]
+    }
+
+    fn count(&self) -> u64 {
+        self.ways(0, 0)
+    }
+
+    fn nth(&self, mut rank: u64) -> Option<Vec<Condition>> {
+        if rank >= self.count() {
+            return None;
+        }
+
+        let len = self.conditions.len();
+        let mut row = Vec::with_capacity(len);
+        let (mut i, mut j) = (0, 0);
+
+        while i < len {
+            if self.conditions[i] != Condition::Damaged {
+                let operational = self.ways(i + 1, j);
+                if rank < operational {
+                    row.push(Condition::Operational);
+                    i += 1;
+                    continue;
+                }
+                rank -= operational;
+            }
+
+            let end = i + self.groups[j];
+            row.resize(end, Condition::Damaged);
+            if end < len {
+                row.push(Condition::Operational);
+            }
+            i = row.len();
+            j += 1;
+        }
+
+        Some(row)
+    }
+}
+
+pub struct Arrangements {
+    table: ArrangementTable,
+    index: u64,
+    count: u64,
+    step: u64,
+}
+
+impl Iterator for Arrangements {
+    type Item = Vec<Condition>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let row = self.table.nth(self.index);
+        self.index = self.index.saturating_add(self.step);
+        row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting() {
+        let counts = [
+            ("???.### 1,1,3", 1, 1),
+            (".??..??...?##. 1,1,3", 4, 16384),
+            ("?#?#?#?#?#?#?#? 1,3,1,6", 1, 1),
+            ("????.#...#... 4,1,1", 1, 16),
+            ("????.######..#####. 1,6,5", 4, 2500),
+            ("?###???????? 3,2,1", 10, 506250),
+        ];
+
+        for (line, folded, unfolded) in counts {
+            let record = ConditionRecord::parse(line);
+            assert_eq!(record.count_arrangements(), folded, "{line}");
+            assert_eq!(record.unfold(5).count_arrangements(), unfolded, "{line}");
+        }
+    }
+
+    #[test]
+    fn enumerating() {
+        let record = ConditionRecord::parse("?###???????? 3,2,1");
+        let arrangements = record.arrangements().collect::<Vec<_>>();
+        assert_eq!(arrangements.len(), 10);
+        assert!(arrangements.iter().all(|row| record.matches(row)));
+        assert_eq!(format_conditions(&arrangements[0]), ".###....##.#");
+        assert_eq!(format_conditions(&arrangements[9]), ".###.##.#...");
+
+        assert_eq!(record.sample(3).count(), 3);
+        assert!(!record.matches(&arrangements[0][1..]));
+    }
+}
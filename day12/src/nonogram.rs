@@ -0,0 +1,281 @@
+use crate::{count_arrangements, format_conditions, Condition};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A 2D nonogram, where every row and column is a condition record of its own.
+///
+/// The format is the same as the puzzle input for the rows, followed by an empty line and the
+/// column groups separated by spaces. A group of `0` marks an empty line:
+///
+/// ```text
+/// ?#? 1
+/// ??? 3
+/// ??? 0
+///
+/// 1 2 1
+/// ```
+#[derive(Debug, Clone)]
+pub struct Nonogram {
+    pub width: usize,
+    pub height: usize,
+    rows: Vec<Vec<usize>>,
+    columns: Vec<Vec<usize>>,
+    cells: Vec<Condition>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub picture: Picture,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    width: usize,
+    cells: Vec<Condition>,
+}
+
+impl Picture {
+    pub fn get(&self, x: usize, y: usize) -> Condition {
+        self.cells[y * self.width + x]
+    }
+}
+
+impl fmt::Display for Picture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.width) {
+            writeln!(f, "{}", format_conditions(row))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+impl Nonogram {
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        let (rows, columns) = input
+            .split_once("\n\n")
+            .ok_or_else(|| anyhow::anyhow!("Missing empty line between rows and columns"))?;
+
+        let records = rows
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                parse_row(line)
+                    .map_err(|err| anyhow::anyhow!("Invalid row on line {}: {err}", i + 1))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let height = records.len();
+        let width = records
+            .first()
+            .map_or(0, |(conditions, _)| conditions.len());
+        if width == 0 {
+            anyhow::bail!("The nonogram has no cells");
+        }
+
+        if let Some((conditions, _)) = records.iter().find(|(c, _)| c.len() != width) {
+            anyhow::bail!(
+                "Row {} has a different width than the first one",
+                format_conditions(conditions)
+            );
+        }
+
+        let columns = columns
+            .split_whitespace()
+            .map(parse_groups)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if columns.len() != width {
+            anyhow::bail!("Expected {width} column groups, got {}", columns.len());
+        }
+
+        let (cells, rows): (Vec<_>, Vec<_>) = records.into_iter().unzip();
+        Ok(Self {
+            width,
+            height,
+            rows,
+            columns,
+            cells: cells.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Fills in the picture, looking for a second solution to tell whether it is unique.
+    pub fn solve(&self) -> Option<Solution> {
+        let mut solutions = vec![];
+        self.search(self.cells.clone(), &mut solutions);
+
+        let unique = solutions.len() == 1;
+        solutions.into_iter().next().map(|cells| Solution {
+            picture: Picture {
+                width: self.width,
+                cells,
+            },
+            unique,
+        })
+    }
+
+    fn search(&self, mut cells: Vec<Condition>, solutions: &mut Vec<Vec<Condition>>) {
+        if !self.propagate(&mut cells) {
+            return;
+        }
+
+        // Propagation stalled, so guess the first unknown cell and go both ways
+        let Some(index) = cells.iter().position(|c| *c == Condition::Unknown) else {
+            solutions.push(cells);
+            return;
+        };
+
+        for guess in [Condition::Damaged, Condition::Operational] {
+            let mut cells = cells.clone();
+            cells[index] = guess;
+            self.search(cells, solutions);
+
+            if solutions.len() > 1 {
+                return;
+            }
+        }
+    }
+
+    /// Solves lines one at a time until nothing changes, marking a cell whenever only one of
+    /// its states leaves the line with any arrangements. Returns `false` on a contradiction.
+    fn propagate(&self, cells: &mut [Condition]) -> bool {
+        let mut queue = (0..self.height)
+            .map(Line::Row)
+            .chain((0..self.width).map(Line::Column))
+            .collect::<VecDeque<_>>();
+        let mut queued_rows = vec![true; self.height];
+        let mut queued_columns = vec![true; self.width];
+
+        while let Some(line) = queue.pop_front() {
+            let (indices, groups): (Vec<usize>, _) = match line {
+                Line::Row(y) => {
+                    queued_rows[y] = false;
+                    let indices = (0..self.width).map(|x| y * self.width + x).collect();
+                    (indices, &self.rows[y])
+                }
+                Line::Column(x) => {
+                    queued_columns[x] = false;
+                    let indices = (0..self.height).map(|y| y * self.width + x).collect();
+                    (indices, &self.columns[x])
+                }
+            };
+
+            let mut conditions = indices.iter().map(|i| cells[*i]).collect::<Vec<_>>();
+            if count_arrangements(&conditions, groups) == 0 {
+                return false;
+            }
+
+            for (offset, &index) in indices.iter().enumerate() {
+                if conditions[offset] != Condition::Unknown {
+                    continue;
+                }
+
+                conditions[offset] = Condition::Damaged;
+                let damaged = count_arrangements(&conditions, groups) > 0;
+                conditions[offset] = Condition::Operational;
+                let operational = count_arrangements(&conditions, groups) > 0;
+
+                conditions[offset] = match (damaged, operational) {
+                    (true, true) => Condition::Unknown,
+                    (true, false) => Condition::Damaged,
+                    (false, true) => Condition::Operational,
+                    (false, false) => return false,
+                };
+
+                if conditions[offset] == Condition::Unknown {
+                    continue;
+                }
+
+                cells[index] = conditions[offset];
+                let (x, y) = (index % self.width, index / self.width);
+                match line {
+                    Line::Row(_) if !queued_columns[x] => {
+                        queued_columns[x] = true;
+                        queue.push_back(Line::Column(x));
+                    }
+                    Line::Column(_) if !queued_rows[y] => {
+                        queued_rows[y] = true;
+                        queue.push_back(Line::Row(y));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Like `ConditionRecord::parse`, but with an error instead of a panic for a malformed row.
+fn parse_row(line: &str) -> anyhow::Result<(Vec<Condition>, Vec<usize>)> {
+    let (conditions, groups) = line
+        .split_once(' ')
+        .ok_or_else(|| anyhow::anyhow!("Missing groups in '{line}'"))?;
+
+    let conditions = conditions
+        .chars()
+        .map(|char| match char {
+            '.' | '#' | '?' => Ok(Condition::from_char(char)),
+            _ => Err(anyhow::anyhow!("Unknown condition char '{char}'")),
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok((conditions, parse_groups(groups)?))
+}
+
+fn parse_groups(groups: &str) -> anyhow::Result<Vec<usize>> {
+    let mut parsed = vec![];
+    for group in groups.split(',') {
+        let group = group
+            .parse::<usize>()
+            .map_err(|_| anyhow::anyhow!("Invalid group '{group}'"))?;
+
+        if group != 0 {
+            parsed.push(group);
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solving() {
+        let nonogram = Nonogram::parse("????? 1,1\n????? 5\n..#.. 1\n\n2 1 2 1 2\n").unwrap();
+        let solution = nonogram.solve().unwrap();
+        assert!(solution.unique);
+        assert_eq!(solution.picture.to_string(), "#...#\n#####\n..#..\n");
+    }
+
+    #[test]
+    fn ambiguous() {
+        let nonogram = Nonogram::parse("?? 1\n?? 1\n\n1 1\n").unwrap();
+        let solution = nonogram.solve().unwrap();
+        assert!(!solution.unique);
+
+        let nonogram = Nonogram::parse("?? 2\n?? 0\n\n0 0\n").unwrap();
+        assert!(nonogram.solve().is_none());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Nonogram::parse("???\n\n1 1 1\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid row on line 1: Missing groups in '???'"
+        );
+
+        let err = Nonogram::parse(" 0\n\n").unwrap_err();
+        assert_eq!(err.to_string(), "The nonogram has no cells");
+        assert!(Nonogram::parse("\n\n").is_err());
+
+        assert!(Nonogram::parse("?x? 1\n\n1 0 0\n").is_err());
+    }
+}
@@ -0,0 +1,13 @@
+use day13::Pattern;
+
+fn main() {
+    let input = include_str!("./input.txt");
+    let corrections = std::env::args()
+        .nth(1)
+        .map_or(0, |arg| arg.parse::<u32>().unwrap());
+
+    for (i, pattern) in input.split("\n\n").enumerate() {
+        let pattern = Pattern::parse(pattern).unwrap();
+        println!("Pattern {}: {}", i + 1, pattern.analyze(corrections));
+    }
+}
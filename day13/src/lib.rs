@@ -0,0 +1,354 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Tile {
+    Ash,
+    Rocks,
+}
+
+impl Tile {
+    pub fn from_char(char: char) -> Self {
+        match char {
+            '.' => Self::Ash,
+            '#' => Self::Rocks,
+            _ => panic!("Invalid tile char '{char}'"),
+        }
+    }
+}
+
+/// A mirror line, placed after the given number of columns or rows.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Line {
+    Vertical(usize),
+    Horizontal(usize),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mirror {
+    pub line: Line,
+    /// Cells that have to be flipped for the reflection to be perfect, as `(x, y)`.
+    pub flips: Vec<(usize, usize)>,
+}
+
+impl Mirror {
+    pub fn score(&self) -> usize {
+        match self.line {
+            Line::Vertical(columns) => columns,
+            Line::Horizontal(rows) => rows * 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SymmetryKind {
+    /// Mirrored along the line going from the top left corner to the bottom right one.
+    Diagonal,
+    /// Mirrored along the line going from the top right corner to the bottom left one.
+    AntiDiagonal,
+    /// Looks the same when rotated by 180°.
+    HalfTurn,
+}
+
+impl fmt::Display for SymmetryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymmetryKind::Diagonal => write!(f, "diagonal"),
+            SymmetryKind::AntiDiagonal => write!(f, "anti-diagonal"),
+            SymmetryKind::HalfTurn => write!(f, "half turn"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Symmetry {
+    pub kind: SymmetryKind,
+    /// Cells that have to be flipped for the symmetry to be perfect, as `(x, y)`.
+    pub flips: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub score: Option<usize>,
+    pub symmetries: Vec<Symmetry>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.score {
+            Some(score) => write!(f, "score {score}")?,
+            None => write!(f, "no mirror")?,
+        }
+
+        for symmetry in &self.symmetries {
+            write!(f, ", {}", symmetry.kind)?;
+            if !symmetry.flips.is_empty() {
+                write!(f, " (flip {:?})", symmetry.flips)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A pattern stored as bitmasks, with bit `x` of `rows[y]` and bit `y` of `columns[x]` set
+/// wherever there are rocks.
+#[derive(Debug)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    rows: Vec<u64>,
+    columns: Vec<u64>,
+}
+
+impl Pattern {
+    /// Fails on anything bigger than 64 by 64, since every row and column has to fit in a
+    /// bitmask.
+    pub fn parse(part: &str) -> anyhow::Result<Self> {
+        let width = part.lines().next().map_or(0, str::len);
+        let height = part.lines().count();
+        if width == 0 {
+            anyhow::bail!("Empty pattern");
+        }
+        if width > 64 || height > 64 {
+            anyhow::bail!("Pattern is {width}x{height}, but patterns can be at most 64x64");
+        }
+        if let Some(y) = part.lines().position(|line| line.len() != width) {
+            anyhow::bail!("Row {} has a different width than the first one", y + 1);
+        }
+        if let Some(char) = part.chars().find(|char| !matches!(char, '.' | '#' | '\n')) {
+            anyhow::bail!("Invalid tile char '{char}'");
+        }
+
+        let mut rows = vec![0; height];
+        let mut columns = vec![0; width];
+        for (y, line) in part.lines().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                if Tile::from_char(char) == Tile::Rocks {
+                    rows[y] |= 1 << x;
+                    columns[x] |= 1 << y;
+                }
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            rows,
+            columns,
+        })
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        if self.rows[y] & (1 << x) != 0 {
+            Some(Tile::Rocks)
+        } else {
+            Some(Tile::Ash)
+        }
+    }
+
+    /// Every mirror line that needs exactly `corrections` cells flipped to become a perfect
+    /// reflection, vertical ones first.
+    pub fn mirrors(&self, corrections: u32) -> Vec<Mirror> {
+        let vertical =
+            find_mirrors(&self.columns, corrections)
+                .into_iter()
+                .map(|(columns, flips)| Mirror {
+                    line: Line::Vertical(columns),
+                    flips,
+                });
+
+        let horizontal = find_mirrors(&self.rows, corrections)
+            .into_iter()
+            .map(|(rows, flips)| Mirror {
+                line: Line::Horizontal(rows),
+                flips: flips.into_iter().map(|(y, x)| (x, y)).collect(),
+            });
+
+        vertical.chain(horizontal).collect()
+    }
+
+    pub fn summarize(&self, corrections: u32) -> Option<usize> {
+        self.mirrors(corrections).first().map(Mirror::score)
+    }
+
+    /// Diagonal, anti-diagonal and half turn symmetries that need exactly `corrections` cells
+    /// flipped. The diagonal ones are only checked on square patterns.
+    pub fn symmetries(&self, corrections: u32) -> Vec<Symmetry> {
+        let (width, height) = (self.width, self.height);
+        let mut symmetries = vec![];
+
+        // Each difference shows up twice, once from either cell, so only one of them is kept
+        if width == height {
+            let diagonal = (0..height).map(|y| (y, self.rows[y] ^ self.columns[y]));
+            symmetries.extend(Self::symmetry(
+                SymmetryKind::Diagonal,
+                diagonal,
+                corrections,
+                |x, y| x > y,
+            ));
+
+            let anti_diagonal = (0..height).map(|y| {
+                let mirrored = reverse(self.columns[width - 1 - y], height);
+                (y, self.rows[y] ^ mirrored)
+            });
+            symmetries.extend(Self::symmetry(
+                SymmetryKind::AntiDiagonal,
+                anti_diagonal,
+                corrections,
+                |x, y| x + y < width - 1,
+            ));
+        }
+
+        let half_turn = (0..height).map(|y| {
+            let rotated = reverse(self.rows[height - 1 - y], width);
+            (y, self.rows[y] ^ rotated)
+        });
+        symmetries.extend(Self::symmetry(
+            SymmetryKind::HalfTurn,
+            half_turn,
+            corrections,
+            |x, y| (y, x) < (height - 1 - y, width - 1 - x),
+        ));
+
+        symmetries
+    }
+
+    fn symmetry(
+        kind: SymmetryKind,
+        differences: impl Iterator<Item = (usize, u64)>,
+        corrections: u32,
+        keep: impl Fn(usize, usize) -> bool,
+    ) -> Option<Symmetry> {
+        let mut flips = vec![];
+        for (y, mut diff) in differences {
+            while diff != 0 {
+                let x = diff.trailing_zeros() as usize;
+                if keep(x, y) {
+                    flips.push((x, y));
+                }
+                diff &= diff - 1;
+            }
+        }
+
+        (flips.len() == corrections as usize).then_some(Symmetry { kind, flips })
+    }
+
+    pub fn analyze(&self, corrections: u32) -> Report {
+        Report {
+            score: self.summarize(corrections),
+            symmetries: self.symmetries(corrections),
+        }
+    }
+}
+
+fn reverse(line: u64, len: usize) -> u64 {
+    line.reverse_bits() >> (64 - len)
+}
+
+/// Finds mirrors between consecutive `lines`, returning how many lines come before each one and
+/// the cells to flip as `(line, bit)` pairs. The flipped cells are always on the side before the
+/// mirror.
+fn find_mirrors(lines: &[u64], corrections: u32) -> Vec<(usize, Vec<(usize, usize)>)> {
+    let mut mirrors = vec![];
+
+    for before in 1..lines.len() {
+        let pairs = (0..before).rev().zip(before..lines.len());
+
+        let mut differences = 0;
+        for (a, b) in pairs.clone() {
+            differences += (lines[a] ^ lines[b]).count_ones();
+            if differences > corrections {
+                break;
+            }
+        }
+
+        if differences != corrections {
+            continue;
+        }
+
+        let mut flips = vec![];
+        for (a, b) in pairs {
+            let mut diff = lines[a] ^ lines[b];
+            while diff != 0 {
+                flips.push((a, diff.trailing_zeros() as usize));
+                diff &= diff - 1;
+            }
+        }
+        mirrors.push((before, flips));
+    }
+
+    mirrors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.##..##.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn reflections() {
+        let patterns = EXAMPLE
+            .split("\n\n")
+            .map(|part| Pattern::parse(part).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(patterns[0].summarize(0), Some(5));
+        assert_eq!(patterns[1].summarize(0), Some(400));
+
+        let smudged = patterns[0].mirrors(1);
+        assert_eq!(smudged.len(), 2);
+        assert_eq!(smudged[0].line, Line::Horizontal(3));
+        assert_eq!(smudged[0].flips, vec![(0, 0)]);
+        assert_eq!(patterns[1].summarize(1), Some(100));
+    }
+
+    #[test]
+    fn symmetries() {
+        let pattern = Pattern::parse("#..\n.#.\n#.#").unwrap();
+        let kinds = |corrections| {
+            pattern
+                .symmetries(corrections)
+                .into_iter()
+                .map(|s| s.kind)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(kinds(0), vec![SymmetryKind::AntiDiagonal]);
+        assert_eq!(
+            kinds(1),
+            vec![SymmetryKind::Diagonal, SymmetryKind::HalfTurn]
+        );
+        assert_eq!(pattern.symmetries(1)[1].flips, vec![(2, 0)]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let wide = ".".repeat(65);
+        let err = Pattern::parse(&wide).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Pattern is 65x1, but patterns can be at most 64x64"
+        );
+        assert!(Pattern::parse("#.\n#").is_err());
+        assert!(Pattern::parse("#x").is_err());
+    }
+}
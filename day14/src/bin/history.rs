@@ -0,0 +1,24 @@
+use day14::{Direction, Platform};
+
+fn main() {
+    let input = include_str!("./input.txt");
+    let mut args = std::env::args().skip(1);
+    let cycles = args.next().map_or(20, |arg| arg.parse::<usize>().unwrap());
+    let dump = args.next().map(|arg| arg.parse::<u64>().unwrap());
+
+    let platform = Platform::parse(input).unwrap();
+    let history = platform.history(cycles, Direction::North);
+
+    for (i, load) in history.loads.iter().enumerate() {
+        println!("{}: {load}", i + 1);
+    }
+    println!(
+        "Repeats every {} cycles after the first {}",
+        history.cycle.period, history.cycle.prefix
+    );
+
+    if let Some(cycle) = dump {
+        println!();
+        print!("{}", platform.after_cycles(cycle));
+    }
+}
@@ -0,0 +1,369 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use util::cycle::{self, Cycle};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Tile {
+    RoundedRock,
+    CubeRock,
+    Empty,
+}
+
+impl Tile {
+    pub fn from_char(char: char) -> Self {
+        match char {
+            'O' => Self::RoundedRock,
+            '#' => Self::CubeRock,
+            '.' => Self::Empty,
+            _ => panic!("Unknown tile '{char}'"),
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::RoundedRock => 'O',
+            Self::CubeRock => '#',
+            Self::Empty => '.',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn from_char(char: char) -> Self {
+        match char {
+            'N' => Self::North,
+            'E' => Self::East,
+            'S' => Self::South,
+            'W' => Self::West,
+            _ => panic!("Unknown direction '{char}'"),
+        }
+    }
+}
+
+/// A run of cells between cube rocks or edges, starting from the end that rounded rocks roll
+/// towards.
+#[derive(Debug, Clone)]
+struct Segment {
+    start: usize,
+    stride: isize,
+    len: usize,
+}
+
+impl Segment {
+    fn cells(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len as isize).map(|i| (self.start as isize + i * self.stride) as usize)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Platform {
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<Tile>,
+    /// Segments for each direction, indexed by `Direction as usize`. Cube rocks never move, so
+    /// these are only worked out once.
+    segments: [Vec<Segment>; 4],
+}
+
+impl Platform {
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        let width = input.lines().next().map_or(0, str::len);
+        if width == 0 {
+            anyhow::bail!("Empty platform");
+        }
+        if let Some(y) = input.lines().position(|line| line.len() != width) {
+            anyhow::bail!("Row {} has a different width than the first one", y + 1);
+        }
+        if let Some(char) = input
+            .lines()
+            .flat_map(str::chars)
+            .find(|c| !"O#.".contains(*c))
+        {
+            anyhow::bail!("Unknown tile '{char}'");
+        }
+
+        let tiles = input
+            .lines()
+            .flat_map(|line| line.chars().map(Tile::from_char))
+            .collect::<Vec<_>>();
+        let height = tiles.len() / width;
+
+        let mut platform = Self {
+            width,
+            height,
+            tiles,
+            segments: Default::default(),
+        };
+
+        let (width, height) = (width as isize, height as isize);
+        for direction in [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ] {
+            // Lines are walked starting from the edge that rocks roll towards
+            let lines = match direction {
+                Direction::North => (0..width).map(|x| (x, width)).collect::<Vec<_>>(),
+                Direction::South => (0..width)
+                    .map(|x| ((height - 1) * width + x, -width))
+                    .collect(),
+                Direction::West => (0..height).map(|y| (y * width, 1)).collect(),
+                Direction::East => (0..height).map(|y| (y * width + width - 1, -1)).collect(),
+            };
+            let len = match direction {
+                Direction::North | Direction::South => height,
+                Direction::East | Direction::West => width,
+            };
+
+            platform.segments[direction as usize] = lines
+                .into_iter()
+                .flat_map(|(start, stride)| platform.find_segments(start, stride, len))
+                .collect();
+        }
+
+        Ok(platform)
+    }
+
+    fn find_segments(&self, start: isize, stride: isize, len: isize) -> Vec<Segment> {
+        let mut segments = vec![];
+        let mut current: Option<Segment> = None;
+
+        for i in 0..len {
+            let index = (start + i * stride) as usize;
+            if self.tiles[index] == Tile::CubeRock {
+                segments.extend(current.take());
+                continue;
+            }
+
+            current
+                .get_or_insert(Segment {
+                    start: index,
+                    stride,
+                    len: 0,
+                })
+                .len += 1;
+        }
+
+        segments.extend(current);
+        segments
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.tiles.get(y * self.width + x).copied()
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    pub fn tilt(&mut self, direction: Direction) {
+        for segment in &self.segments[direction as usize] {
+            let rounded = segment
+                .cells()
+                .filter(|index| self.tiles[*index] == Tile::RoundedRock)
+                .count();
+
+            for (i, index) in segment.cells().enumerate() {
+                self.tiles[index] = if i < rounded {
+                    Tile::RoundedRock
+                } else {
+                    Tile::Empty
+                };
+            }
+        }
+    }
+
+    pub fn tilt_all(&mut self, directions: &[Direction]) {
+        for direction in directions {
+            self.tilt(*direction);
+        }
+    }
+
+    pub fn cycle(&mut self) {
+        self.tilt_all(&[
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ]);
+    }
+
+    pub fn load(&self) -> usize {
+        self.load_on(Direction::North)
+    }
+
+    /// Load on the support beams along the given edge, where each rounded rock counts as much as
+    /// its distance from the opposite edge.
+    pub fn load_on(&self, edge: Direction) -> usize {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| **tile == Tile::RoundedRock)
+            .map(|(index, _)| {
+                let (x, y) = (index % self.width, index / self.width);
+                match edge {
+                    Direction::North => self.height - y,
+                    Direction::East => x + 1,
+                    Direction::South => y + 1,
+                    Direction::West => self.width - x,
+                }
+            })
+            .sum()
+    }
+
+    fn spun(&self) -> Self {
+        let mut platform = self.clone();
+        platform.cycle();
+        platform
+    }
+
+    pub fn find_cycle(&self) -> Cycle {
+        cycle::hashed(self.clone(), Self::spun)
+    }
+
+    /// The platform after `cycles` spin cycles, skipping ahead once the states start repeating.
+    pub fn after_cycles(&self, cycles: u64) -> Self {
+        self.find_cycle()
+            .state_after(self.clone(), Self::spun, cycles)
+    }
+
+    pub fn history(&self, cycles: usize, edge: Direction) -> History {
+        let mut platform = self.clone();
+        let loads = (0..cycles)
+            .map(|_| {
+                platform.cycle();
+                platform.load_on(edge)
+            })
+            .collect();
+
+        History {
+            loads,
+            cycle: self.find_cycle(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    /// Load after each of the first cycles, so `loads[0]` is the load after one cycle.
+    pub loads: Vec<usize>,
+    pub cycle: Cycle,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.tiles.chunks(self.width) {
+            let row = row.iter().map(|tile| tile.to_char()).collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Platform {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.tiles == other.tiles
+    }
+}
+
+impl Eq for Platform {}
+
+impl Hash for Platform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.tiles.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_square() {
+        let mut platform = Platform::parse("O.#O.\n.O..O\n").unwrap();
+        platform.tilt(Direction::North);
+        assert_eq!(platform.load(), 8);
+
+        platform.tilt(Direction::East);
+        let rounded = |platform: &Platform| {
+            (0..platform.height)
+                .flat_map(|y| (0..platform.width).map(move |x| (x, y)))
+                .filter(|(x, y)| platform.get(*x, *y) == Some(Tile::RoundedRock))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rounded(&platform), vec![(0, 0), (1, 0), (3, 0), (4, 0)]);
+
+        platform.tilt_all(&[Direction::South, Direction::West]);
+        assert_eq!(rounded(&platform), vec![(0, 1), (1, 1), (2, 1), (3, 1)]);
+        assert_eq!(platform.load_on(Direction::South), 8);
+        assert_eq!(platform.load_on(Direction::West), 5 + 4 + 3 + 2);
+        assert_eq!(platform.load_on(Direction::East), 1 + 2 + 3 + 4);
+    }
+
+    #[test]
+    fn history() {
+        let input = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+";
+        let platform = Platform::parse(input).unwrap();
+        let history = platform.history(10, Direction::North);
+        assert_eq!(history.loads[..3], [87, 69, 69]);
+        assert_eq!(
+            history.cycle,
+            Cycle {
+                prefix: 3,
+                period: 7
+            }
+        );
+        assert_eq!(platform.after_cycles(1_000_000_000).load(), 64);
+        assert_eq!(
+            platform.after_cycles(1).to_string(),
+            "\
+.....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+"
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Platform::parse("O..\n#\n...\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Row 2 has a different width than the first one"
+        );
+        assert!(Platform::parse("").is_err());
+        assert!(Platform::parse("O.x\n").is_err());
+    }
+}
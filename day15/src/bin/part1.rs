@@ -1,15 +1,11 @@
+use day15::{hash, raw_steps};
+
 fn main() {
     let input = include_str!("./input.txt");
     let output = process(input);
     println!("{}", output);
 }
 
-fn hash(input: &str) -> u32 {
-    input.chars().fold(0, |acc, c| {
-        (acc + c as u32) * 17 % 256
-    })
-}
-
 fn process(input: &str) -> u32 {
-    input.strip_suffix('\n').unwrap().split(',').map(hash).sum()
+    raw_steps(input).map(|step| hash(step) as u32).sum()
 }
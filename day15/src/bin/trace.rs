@@ -0,0 +1,15 @@
+use day15::{parse_steps, LensBoxes};
+
+fn main() {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+
+    let mut boxes = LensBoxes::with_trace();
+    for step in parse_steps(&input) {
+        boxes.apply(step.unwrap());
+    }
+
+    print!("{}", boxes.render_trace());
+}
@@ -0,0 +1,302 @@
+use anyhow::Context;
+use smallvec::SmallVec;
+use std::borrow::Borrow;
+use std::fmt;
+
+pub const SIZE: usize = 256;
+
+/// The Holiday ASCII String Helper algorithm.
+pub fn hash(input: impl AsRef<[u8]>) -> u8 {
+    input
+        .as_ref()
+        .iter()
+        .fold(0u8, |acc, byte| acc.wrapping_add(*byte).wrapping_mul(17))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Operation {
+    Add(u8),
+    Remove,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Step<'a> {
+    pub label: &'a str,
+    pub operation: Operation,
+}
+
+impl<'a> Step<'a> {
+    pub fn parse(step: &'a str) -> anyhow::Result<Self> {
+        let (label, operation) = if let Some(label) = step.strip_suffix('-') {
+            (label, Operation::Remove)
+        } else if let Some((label, length)) = step.split_once('=') {
+            let length = length
+                .parse::<u8>()
+                .with_context(|| format!("Invalid focal length '{length}'"))?;
+            (label, Operation::Add(length))
+        } else {
+            anyhow::bail!("Missing operation");
+        };
+
+        if label.is_empty() {
+            anyhow::bail!("Missing label");
+        }
+
+        Ok(Self { label, operation })
+    }
+}
+
+impl fmt::Display for Step<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation {
+            Operation::Add(length) => write!(f, "{}={length}", self.label),
+            Operation::Remove => write!(f, "{}-", self.label),
+        }
+    }
+}
+
+/// Splits the initialization sequence into steps, ignoring whitespace and newlines around them
+/// as well as empty steps.
+pub fn raw_steps(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+}
+
+pub fn parse_steps(input: &str) -> impl Iterator<Item = anyhow::Result<Step<'_>>> {
+    raw_steps(input).enumerate().map(|(i, step)| {
+        Step::parse(step).with_context(|| format!("Invalid step #{} '{step}'", i + 1))
+    })
+}
+
+/// The Holiday ASCII String Helper Manual Arrangement Procedure: 256 boxes, each keeping its
+/// entries in insertion order, with the box picked by hashing the key.
+#[derive(Debug, Clone)]
+pub struct LensBoxes<K, V> {
+    boxes: [SmallVec<[(K, V); 5]>; SIZE],
+    trace: Option<Vec<TraceEntry<K, V>>>,
+}
+
+/// The boxes right after a step, displayed like the puzzle's walkthrough.
+#[derive(Debug, Clone)]
+pub struct TraceEntry<K, V> {
+    pub step: String,
+    /// The box the step changed, if any. Removing a lens that isn't there changes nothing.
+    pub changed: Option<u8>,
+    /// Every box with lenses in it, like in the walkthrough.
+    pub boxes: Vec<(u8, Vec<(K, V)>)>,
+}
+
+impl<K: fmt::Display, V: fmt::Display> fmt::Display for TraceEntry<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "After \"{}\":", self.step)?;
+        for (number, lenses) in &self.boxes {
+            write!(f, "Box {number}:")?;
+            for (key, value) in lenses {
+                write!(f, " [{key} {value}]")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K: AsRef<[u8]> + Eq, V> Default for LensBoxes<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: AsRef<[u8]> + Eq, V> LensBoxes<K, V> {
+    pub fn new() -> Self {
+        Self {
+            boxes: std::array::from_fn(|_| SmallVec::new()),
+            trace: None,
+        }
+    }
+
+    /// Like `new`, but keeps track of the boxes after each step passed to `apply`. Calling
+    /// `insert` or `remove` directly doesn't add to the trace, since there's no step to show.
+    pub fn with_trace() -> Self {
+        Self {
+            trace: Some(vec![]),
+            ..Self::new()
+        }
+    }
+
+    pub fn trace(&self) -> &[TraceEntry<K, V>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Replaces the value in place if the key is already in its box, otherwise puts it at the
+    /// back.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let lenses = &mut self.boxes[hash(&key) as usize];
+        match lenses.iter_mut().find(|(ikey, _)| *ikey == key) {
+            Some((_, ivalue)) => Some(std::mem::replace(ivalue, value)),
+            None => {
+                lenses.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: AsRef<[u8]> + Eq + ?Sized,
+    {
+        let lenses = &mut self.boxes[hash(key) as usize];
+        let index = lenses.iter().position(|(ikey, _)| ikey.borrow() == key)?;
+        Some(lenses.remove(index).1)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: AsRef<[u8]> + Eq + ?Sized,
+    {
+        self.boxes[hash(key) as usize]
+            .iter()
+            .find(|(ikey, _)| ikey.borrow() == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_box(&self, number: u8) -> &[(K, V)] {
+        &self.boxes[number as usize]
+    }
+
+    /// Every entry in focusing power order, along with its box number and slot within the box.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, usize, &K, &V)> {
+        self.boxes.iter().enumerate().flat_map(|(number, lenses)| {
+            lenses
+                .iter()
+                .enumerate()
+                .map(move |(slot, (key, value))| (number as u8, slot, key, value))
+        })
+    }
+}
+
+impl<'a> LensBoxes<&'a str, u8> {
+    pub fn apply(&mut self, step: Step<'a>) {
+        let changed = match step.operation {
+            Operation::Add(length) => {
+                self.insert(step.label, length);
+                true
+            }
+            Operation::Remove => self.remove(step.label).is_some(),
+        };
+
+        if let Some(trace) = &mut self.trace {
+            let boxes = (0..SIZE)
+                .filter(|number| !self.boxes[*number].is_empty())
+                .map(|number| (number as u8, self.boxes[number].to_vec()))
+                .collect();
+            trace.push(TraceEntry {
+                step: step.to_string(),
+                changed: changed.then(|| hash(step.label)),
+                boxes,
+            });
+        }
+    }
+
+    pub fn render_trace(&self) -> String {
+        self.trace()
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn focusing_power(&self) -> usize {
+        self.iter()
+            .map(|(number, slot, _, length)| (number as usize + 1) * (slot + 1) * *length as usize)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let step = Step::parse("rn=69").unwrap();
+        assert_eq!(step.label, "rn");
+        assert_eq!(step.operation, Operation::Add(69));
+
+        let step = Step::parse("abcde-").unwrap();
+        assert_eq!(step.label, "abcde");
+        assert_eq!(step.operation, Operation::Remove);
+
+        let steps = parse_steps("rn=1,\ncm-, qp=3\n").collect::<Vec<_>>();
+        assert_eq!(steps.len(), 3);
+        assert!(steps.iter().all(|step| step.is_ok()));
+
+        let errors = parse_steps("rn=1,cm,=3,qp=x")
+            .filter_map(|step| step.err())
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "Invalid step #2 'cm'",
+                "Invalid step #3 '=3'",
+                "Invalid step #4 'qp=x'"
+            ]
+        );
+    }
+
+    #[test]
+    fn boxes() {
+        let mut boxes = LensBoxes::new();
+        for step in parse_steps("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7") {
+            boxes.apply(step.unwrap());
+        }
+
+        assert_eq!(boxes.get_box(0), [("rn", 1), ("cm", 2)]);
+        assert_eq!(boxes.get_box(3), [("ot", 7), ("ab", 5), ("pc", 6)]);
+        assert_eq!(boxes.get("ot"), Some(&7));
+        assert_eq!(boxes.get("qp"), None);
+        assert_eq!(boxes.focusing_power(), 145);
+        assert!(boxes.trace().is_empty());
+    }
+
+    #[test]
+    fn tracing() {
+        let mut boxes = LensBoxes::with_trace();
+        for step in parse_steps("rn=1,cm-,qp=3,cm=2,qp-") {
+            boxes.apply(step.unwrap());
+        }
+
+        assert_eq!(
+            boxes.render_trace(),
+            "\
+After \"rn=1\":
+Box 0: [rn 1]
+
+After \"cm-\":
+Box 0: [rn 1]
+
+After \"qp=3\":
+Box 0: [rn 1]
+Box 1: [qp 3]
+
+After \"cm=2\":
+Box 0: [rn 1] [cm 2]
+Box 1: [qp 3]
+
+After \"qp-\":
+Box 0: [rn 1] [cm 2]
+"
+        );
+
+        let changed = boxes
+            .trace()
+            .iter()
+            .map(|entry| entry.changed)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, [Some(0), None, Some(1), Some(0), Some(1)]);
+    }
+}
@@ -0,0 +1,42 @@
+use day16::{Beam, Direction, Grid, Simulation};
+use std::path::PathBuf;
+
+fn main() {
+    let input = include_str!("./input.txt");
+    let grid = Grid::parse(input);
+    let mut simulation = Simulation::new(&grid, Beam::new(0, 0, Direction::East));
+
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("frames") => {
+            let mut frame = 0;
+            loop {
+                println!("Step {frame}:");
+                println!("{}", simulation.render_highlighted());
+                if !simulation.step() {
+                    break;
+                }
+                frame += 1;
+            }
+        }
+        Some("ppm") => {
+            let dir = PathBuf::from(args.next().expect("Missing output directory"));
+            std::fs::create_dir_all(&dir).unwrap();
+
+            let mut frame = 0;
+            loop {
+                let path = dir.join(format!("frame_{frame:04}.ppm"));
+                std::fs::write(path, simulation.to_ppm(4)).unwrap();
+                if !simulation.step() {
+                    break;
+                }
+                frame += 1;
+            }
+            println!("Wrote {} frames to {}", frame + 1, dir.display());
+        }
+        _ => {
+            while simulation.step() {}
+            print!("{}", simulation.render_highlighted());
+        }
+    }
+}
@@ -0,0 +1,503 @@
+use std::{collections::HashSet, mem};
+
+pub mod render;
+
+#[derive(Debug, Clone, Copy)]
+pub enum MirrorType {
+    Forward,
+    Backward,
+}
+
+impl MirrorType {
+    pub fn next_direction(&self, current: Direction) -> Direction {
+        match self {
+            Self::Forward => match current {
+                Direction::North => Direction::East,
+                Direction::East => Direction::North,
+                Direction::South => Direction::West,
+                Direction::West => Direction::South,
+            },
+            Self::Backward => match current {
+                Direction::North => Direction::West,
+                Direction::East => Direction::South,
+                Direction::South => Direction::East,
+                Direction::West => Direction::North,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SplitterType {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Tile {
+    Empty,
+    Mirror(MirrorType),
+    Splitter(SplitterType),
+}
+
+impl Tile {
+    pub fn from_char(char: char) -> Self {
+        match char {
+            '.' => Self::Empty,
+            '/' => Self::Mirror(MirrorType::Forward),
+            '\\' => Self::Mirror(MirrorType::Backward),
+            '|' => Self::Splitter(SplitterType::Vertical),
+            '-' => Self::Splitter(SplitterType::Horizontal),
+            _ => panic!("Unknown tile char '{char}'"),
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::Empty => '.',
+            Self::Mirror(MirrorType::Forward) => '/',
+            Self::Mirror(MirrorType::Backward) => '\\',
+            Self::Splitter(SplitterType::Vertical) => '|',
+            Self::Splitter(SplitterType::Horizontal) => '-',
+        }
+    }
+
+    /// Directions a beam coming in with `direction` leaves this tile in.
+    pub fn outputs(&self, direction: Direction) -> (Direction, Option<Direction>) {
+        match (self, direction) {
+            (Tile::Splitter(SplitterType::Horizontal), Direction::North | Direction::South) => {
+                (Direction::East, Some(Direction::West))
+            }
+            (Tile::Splitter(SplitterType::Vertical), Direction::West | Direction::East) => {
+                (Direction::South, Some(Direction::North))
+            }
+            (Tile::Mirror(t), _) => (t.next_direction(direction), None),
+            _ => (direction, None),
+        }
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn dx(&self) -> isize {
+        match self {
+            Self::North => 0,
+            Self::East => 1,
+            Self::South => 0,
+            Self::West => -1,
+        }
+    }
+
+    pub fn dy(&self) -> isize {
+        match self {
+            Self::North => -1,
+            Self::East => 0,
+            Self::South => 1,
+            Self::West => 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<Tile>,
+}
+
+impl Grid {
+    pub fn parse(input: &str) -> Self {
+        let tiles = input
+            .lines()
+            .flat_map(|line| line.chars().map(Tile::from_char))
+            .collect::<Vec<_>>();
+        let width = input.lines().next().unwrap().len();
+        let height = tiles.len() / width;
+
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<Tile> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        if x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+
+        self.tiles
+            .get(y as usize * self.width + x as usize)
+            .copied()
+    }
+
+    /// Beams entering the grid from every edge tile, pointing inwards.
+    pub fn edge_beams(&self) -> Vec<Beam> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let mut beams = vec![];
+
+        for x in 0..width {
+            beams.push(Beam::new(x, 0, Direction::South));
+            beams.push(Beam::new(x, height - 1, Direction::North));
+        }
+
+        for y in 0..height {
+            beams.push(Beam::new(0, y, Direction::East));
+            beams.push(Beam::new(width - 1, y, Direction::West));
+        }
+
+        beams
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub struct Beam {
+    pub x: isize,
+    pub y: isize,
+    pub direction: Direction,
+}
+
+impl Beam {
+    pub fn new(x: isize, y: isize, direction: Direction) -> Self {
+        Self { x, y, direction }
+    }
+
+    fn moved(self, direction: Direction) -> Self {
+        Self {
+            x: self.x + direction.dx(),
+            y: self.y + direction.dy(),
+            direction,
+        }
+    }
+}
+
+/// Follows every beam one tile at a time, returning the number of energized tiles.
+pub fn simulate(grid: &Grid, beam: Beam) -> usize {
+    let mut simulation = Simulation::new(grid, beam);
+    while simulation.step() {}
+    simulation.energized.len()
+}
+
+/// Beams being followed one propagation step at a time, so the state in between steps can be
+/// looked at.
+#[derive(Debug)]
+pub struct Simulation<'a> {
+    grid: &'a Grid,
+    states: HashSet<Beam>,
+    energized: HashSet<(isize, isize)>,
+    current_beams: Vec<Beam>,
+    next_beams: Vec<Beam>,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(grid: &'a Grid, beam: Beam) -> Self {
+        Self {
+            grid,
+            states: HashSet::new(),
+            energized: HashSet::new(),
+            current_beams: vec![beam],
+            next_beams: vec![],
+        }
+    }
+
+    /// Moves every beam forward by one tile. Returns `false` once there's nothing left to move.
+    pub fn step(&mut self) -> bool {
+        if self.current_beams.is_empty() {
+            return false;
+        }
+
+        for beam in self.current_beams.drain(..) {
+            let Some(tile) = self.grid.get(beam.x, beam.y) else {
+                continue;
+            };
+
+            self.energized.insert((beam.x, beam.y));
+
+            if !self.states.insert(beam) {
+                continue;
+            }
+
+            let (first, second) = tile.outputs(beam.direction);
+            self.next_beams.push(beam.moved(first));
+            if let Some(second) = second {
+                self.next_beams.push(beam.moved(second));
+            }
+        }
+
+        mem::swap(&mut self.current_beams, &mut self.next_beams);
+        true
+    }
+
+    pub fn energized(&self) -> usize {
+        self.energized.len()
+    }
+
+    pub fn is_energized(&self, x: isize, y: isize) -> bool {
+        self.energized.contains(&(x, y))
+    }
+
+    /// Beams that will be moved by the next step.
+    pub fn beams(&self) -> &[Beam] {
+        &self.current_beams
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Bitset(Vec<u64>);
+
+impl Bitset {
+    fn new(bits: usize) -> Self {
+        Self(vec![0; bits.div_ceil(64)])
+    }
+
+    fn insert(&mut self, bit: usize) {
+        self.0[bit / 64] |= 1 << (bit % 64);
+    }
+
+    fn union(&mut self, other: &Bitset) {
+        for (a, b) in self.0.iter_mut().zip(&other.0) {
+            *a |= b;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/// A straight run of tiles that a beam leaving a mirror or splitter goes through, up to and
+/// including the next mirror or splitter.
+#[derive(Debug)]
+struct Segment {
+    tiles: Vec<usize>,
+    next: Vec<usize>,
+}
+
+/// The contraption compiled into a graph of beam segments. Segments that can reach each other
+/// are collapsed into strongly connected components, so the tiles energized from any of them
+/// only have to be worked out once.
+#[derive(Debug)]
+pub struct BeamGraph<'a> {
+    grid: &'a Grid,
+    /// Segment leaving each tile in each direction, indexed by `tile * 4 + direction`
+    segment_ids: Vec<Option<usize>>,
+    component_of: Vec<usize>,
+    energized: Vec<Bitset>,
+}
+
+impl<'a> BeamGraph<'a> {
+    pub fn compile(grid: &'a Grid) -> Self {
+        let mut graph = Self {
+            grid,
+            segment_ids: vec![None; grid.width * grid.height * 4],
+            component_of: vec![],
+            energized: vec![],
+        };
+
+        let mut segments = vec![];
+        for y in 0..grid.height as isize {
+            for x in 0..grid.width as isize {
+                if matches!(grid.get(x, y), Some(Tile::Empty)) {
+                    continue;
+                }
+
+                for direction in [
+                    Direction::North,
+                    Direction::East,
+                    Direction::South,
+                    Direction::West,
+                ] {
+                    let id = graph.segment_id(Beam::new(x, y, direction)).unwrap();
+                    graph.segment_ids[id] = Some(segments.len());
+                    segments.push(Beam::new(x, y, direction).moved(direction));
+                }
+            }
+        }
+
+        let segments = segments
+            .into_iter()
+            .map(|start| {
+                let (tiles, next) = graph.trace(start);
+                Segment { tiles, next }
+            })
+            .collect::<Vec<_>>();
+
+        let components = strongly_connected_components(&segments);
+        graph.component_of = vec![0; segments.len()];
+        for (component, members) in components.iter().enumerate() {
+            for member in members {
+                graph.component_of[*member] = component;
+            }
+        }
+
+        // Components come out in reverse topological order, so everything a component leads to
+        // has already been filled in by the time it's reached
+        let tiles = grid.width * grid.height;
+        for members in &components {
+            let mut energized = Bitset::new(tiles);
+            for member in members {
+                for tile in &segments[*member].tiles {
+                    energized.insert(*tile);
+                }
+
+                for next in &segments[*member].next {
+                    let component = graph.component_of[*next];
+                    if component < graph.energized.len() {
+                        energized.union(&graph.energized[component]);
+                    }
+                }
+            }
+            graph.energized.push(energized);
+        }
+
+        graph
+    }
+
+    fn segment_id(&self, beam: Beam) -> Option<usize> {
+        self.grid.get(beam.x, beam.y)?;
+        let tile = beam.y as usize * self.grid.width + beam.x as usize;
+        Some(tile * 4 + beam.direction as usize)
+    }
+
+    /// Walks from `start` up to the next mirror or splitter, returning the tiles passed and the
+    /// segments the beam continues into.
+    fn trace(&self, start: Beam) -> (Vec<usize>, Vec<usize>) {
+        let mut beam = start;
+        let mut tiles = vec![];
+
+        while let Some(tile) = self.grid.get(beam.x, beam.y) {
+            tiles.push(beam.y as usize * self.grid.width + beam.x as usize);
+            if matches!(tile, Tile::Empty) {
+                beam = beam.moved(beam.direction);
+                continue;
+            }
+
+            let (first, second) = tile.outputs(beam.direction);
+            let next = [Some(first), second]
+                .into_iter()
+                .flatten()
+                .filter_map(|direction| {
+                    let id = self.segment_id(Beam::new(beam.x, beam.y, direction))?;
+                    self.segment_ids[id]
+                })
+                .collect();
+            return (tiles, next);
+        }
+
+        (tiles, vec![])
+    }
+
+    /// Number of tiles energized by a beam starting at the given tile.
+    pub fn energized(&self, beam: Beam) -> usize {
+        let (tiles, next) = self.trace(beam);
+        let mut energized = Bitset::new(self.grid.width * self.grid.height);
+        for tile in tiles {
+            energized.insert(tile);
+        }
+
+        for segment in next {
+            energized.union(&self.energized[self.component_of[segment]]);
+        }
+        energized.len()
+    }
+}
+
+/// Tarjan's algorithm, without recursion since the segment chains can get pretty long.
+fn strongly_connected_components(segments: &[Segment]) -> Vec<Vec<usize>> {
+    let mut index = vec![None; segments.len()];
+    let mut lowlink = vec![0; segments.len()];
+    let mut on_stack = vec![false; segments.len()];
+    let mut stack = vec![];
+    let mut components = vec![];
+    let mut counter = 0;
+
+    for root in 0..segments.len() {
+        if index[root].is_some() {
+            continue;
+        }
+
+        let mut work = vec![(root, 0)];
+        while let Some((node, child)) = work.pop() {
+            if child == 0 {
+                index[node] = Some(counter);
+                lowlink[node] = counter;
+                counter += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            } else {
+                let previous = segments[node].next[child - 1];
+                if on_stack[previous] {
+                    lowlink[node] = lowlink[node].min(lowlink[previous]);
+                }
+            }
+
+            if let Some(&next) = segments[node].next.get(child) {
+                work.push((node, child + 1));
+                match index[next] {
+                    None => work.push((next, 0)),
+                    Some(next_index) if on_stack[next] => {
+                        lowlink[node] = lowlink[node].min(next_index);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            if Some(lowlink[node]) == index[node] {
+                let mut component = vec![];
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+";
+
+    #[test]
+    fn graph_matches_simulation() {
+        let grid = Grid::parse(EXAMPLE);
+        let graph = BeamGraph::compile(&grid);
+
+        assert_eq!(graph.energized(Beam::new(0, 0, Direction::East)), 46);
+        assert_eq!(graph.energized(Beam::new(3, 0, Direction::South)), 51);
+
+        for beam in grid.edge_beams() {
+            assert_eq!(graph.energized(beam), simulate(&grid, beam), "{beam:?}");
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{Direction, Simulation, Tile};
+
+impl Direction {
+    fn arrow(&self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+}
+
+impl Simulation<'_> {
+    fn cells(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        let (width, height) = (self.grid.width as isize, self.grid.height as isize);
+        (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Directions of the beams that went through each tile so far.
+    fn passed(&self) -> Vec<Vec<Direction>> {
+        let mut passed = vec![vec![]; self.grid.width * self.grid.height];
+        for beam in &self.states {
+            passed[beam.y as usize * self.grid.width + beam.x as usize].push(beam.direction);
+        }
+        passed
+    }
+
+    fn render_with(&self, style: impl Fn(isize, isize, char) -> String) -> String {
+        let passed = self.passed();
+        let mut output = String::new();
+
+        for (x, y) in self.cells() {
+            let tile = self.grid.get(x, y).unwrap();
+            let directions = &passed[y as usize * self.grid.width + x as usize];
+
+            let char = match (tile, directions.len()) {
+                (Tile::Empty, 1) => directions[0].arrow(),
+                (Tile::Empty, 2..=4) => char::from_digit(directions.len() as u32, 10).unwrap(),
+                _ => tile.to_char(),
+            };
+
+            output.push_str(&style(x, y, char));
+            if x as usize == self.grid.width - 1 {
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    /// Draws the contraption with the beams that went through each empty tile on top, the same
+    /// way the puzzle does: an arrow for a single beam, or the number of beams if there are more.
+    pub fn render(&self) -> String {
+        self.render_with(|_, _, char| char.to_string())
+    }
+
+    /// Like `render`, but with the energized tiles and the beams about to move coloured using
+    /// ANSI escape codes.
+    pub fn render_highlighted(&self) -> String {
+        self.render_with(|x, y, char| {
+            if self
+                .current_beams
+                .iter()
+                .any(|beam| (beam.x, beam.y) == (x, y))
+            {
+                format!("\x1b[1;31m{char}\x1b[0m")
+            } else if self.is_energized(x, y) {
+                format!("\x1b[1;33m{char}\x1b[0m")
+            } else {
+                format!("\x1b[2m{char}\x1b[0m")
+            }
+        })
+    }
+
+    /// Energized tiles as `#` and the rest as `.`.
+    pub fn render_energized(&self) -> String {
+        self.render_with(|x, y, _| if self.is_energized(x, y) { "#" } else { "." }.to_string())
+    }
+
+    /// A binary PPM image of the current state, with every tile drawn as a `scale` by `scale`
+    /// square.
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let (width, height) = (self.grid.width * scale, self.grid.height * scale);
+        let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+
+        let colours = self
+            .cells()
+            .map(|(x, y)| {
+                let head = self.current_beams.iter().any(|b| (b.x, b.y) == (x, y));
+                let empty = matches!(self.grid.get(x, y), Some(Tile::Empty));
+
+                match (head, self.is_energized(x, y), empty) {
+                    (true, _, _) => [230, 40, 40],
+                    (false, true, true) => [120, 90, 20],
+                    (false, true, false) => [255, 220, 80],
+                    (false, false, true) => [20, 20, 30],
+                    (false, false, false) => [110, 110, 130],
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for row in colours.chunks(self.grid.width) {
+            for _ in 0..scale {
+                for colour in row {
+                    for _ in 0..scale {
+                        image.extend_from_slice(colour);
+                    }
+                }
+            }
+        }
+
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Beam, Grid};
+
+    use super::*;
+
+    #[test]
+    fn puzzle_diagrams() {
+        let grid = Grid::parse(
+            r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+",
+        );
+        let mut simulation = Simulation::new(&grid, Beam::new(0, 0, Direction::East));
+        while simulation.step() {}
+
+        assert_eq!(
+            simulation.render(),
+            r">|<<<\....
+|v-.\^....
+.v...|->>>
+.v...v^.|.
+.v...v^...
+.v...v^..\
+.v../2\\..
+<->-/vv|..
+.|<<<2-|.\
+.v//.|.v..
+"
+        );
+        assert_eq!(
+            simulation.render_energized(),
+            "\
+######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#..
+"
+        );
+        assert_eq!(
+            simulation.to_ppm(2).len(),
+            "P6\n20 20\n255\n".len() + 20 * 20 * 3
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::time::Instant;
+
+use day17::{Backend, Crucible, Grid};
+
+fn main() {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+    let grid = Grid::parse(&input);
+
+    for (name, crucible) in [
+        ("regular", Crucible::regular()),
+        ("ultra", Crucible::ultra()),
+    ] {
+        for backend in [Backend::Dijkstra, Backend::AStar] {
+            let start = Instant::now();
+            let (route, stats) = crucible.solve_with(&grid, backend);
+            let elapsed = start.elapsed();
+
+            let heat_loss = match route {
+                Ok(route) => route.heat_loss().to_string(),
+                Err(err) => err.to_string(),
+            };
+            println!(
+                "{name:<8} {:<8} heat loss {heat_loss:<6} expanded {:<8} pushed {:<8} {elapsed:?}",
+                format!("{backend:?}"),
+                stats.expanded,
+                stats.pushed,
+            );
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use day17::{Crucible, Grid};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let crucible = match args.next().as_deref() {
+        Some("ultra") => Crucible::ultra(),
+        Some("regular") | None => Crucible::regular(),
+        Some(other) => panic!("Unknown crucible '{other}'"),
+    };
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+
+    let grid = Grid::parse(&input);
+    match crucible.solve(&grid) {
+        Ok(route) => {
+            print!("{}", route.render(&grid));
+            println!("Heat loss: {}", route.heat_loss());
+        }
+        Err(err) => eprintln!("{err}"),
+    }
+}
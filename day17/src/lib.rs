@@ -0,0 +1,397 @@
+use std::fmt;
+use util::search::{self, Search, Stats};
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn dx(&self) -> isize {
+        match self {
+            Self::North => 0,
+            Self::East => 1,
+            Self::South => 0,
+            Self::West => -1,
+        }
+    }
+
+    pub fn dy(&self) -> isize {
+        match self {
+            Self::North => -1,
+            Self::East => 0,
+            Self::South => 1,
+            Self::West => 0,
+        }
+    }
+
+    pub fn right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn left(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+        }
+    }
+
+    pub fn reverse(&self) -> Direction {
+        self.right().right()
+    }
+
+    pub fn arrow(&self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<u32>,
+}
+
+impl Grid {
+    pub fn parse(input: &str) -> Self {
+        let tiles = input
+            .lines()
+            .flat_map(|line| line.chars().map(|c| c.to_digit(10).unwrap()))
+            .collect::<Vec<_>>();
+        let width = input.lines().next().unwrap().len();
+        let height = tiles.len() / width;
+
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<u32> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        if x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+
+        self.tiles
+            .get(y as usize * self.width + x as usize)
+            .copied()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Turn {
+    Left,
+    Right,
+    Reverse,
+}
+
+impl Turn {
+    fn apply(&self, direction: Direction) -> Direction {
+        match self {
+            Turn::Left => direction.left(),
+            Turn::Right => direction.right(),
+            Turn::Reverse => direction.reverse(),
+        }
+    }
+}
+
+/// Where a crucible is, which way it's facing and how many blocks it has moved in a straight
+/// line so far.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct State {
+    pub x: isize,
+    pub y: isize,
+    pub direction: Direction,
+    pub straight: u32,
+}
+
+impl State {
+    fn next(&self, direction: Direction) -> Self {
+        let straight = if self.direction == direction {
+            self.straight + 1
+        } else {
+            1
+        };
+
+        Self {
+            x: self.x + direction.dx(),
+            y: self.y + direction.dy(),
+            direction,
+            straight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Backend {
+    Dijkstra,
+    AStar,
+}
+
+#[derive(Debug, Clone)]
+pub struct Crucible {
+    /// Blocks it has to move in a straight line before it can turn or stop.
+    pub min_straight: u32,
+    /// Blocks it can move in a straight line at most before it has to turn.
+    pub max_straight: u32,
+    pub turns: Vec<Turn>,
+    pub start: (isize, isize),
+    /// Defaults to the bottom right corner when not set.
+    pub end: Option<(isize, isize)>,
+}
+
+impl Crucible {
+    pub fn new(min_straight: u32, max_straight: u32) -> Self {
+        Self {
+            min_straight,
+            max_straight,
+            turns: vec![Turn::Left, Turn::Right],
+            start: (0, 0),
+            end: None,
+        }
+    }
+
+    pub fn regular() -> Self {
+        Self::new(1, 3)
+    }
+
+    pub fn ultra() -> Self {
+        Self::new(4, 10)
+    }
+
+    fn successors(&self, grid: &Grid, state: &State, buffer: &mut Vec<(State, u32)>) {
+        let straight = (state.straight < self.max_straight).then_some(state.direction);
+        let turns = self
+            .turns
+            .iter()
+            .filter(|_| state.straight >= self.min_straight)
+            .map(|turn| turn.apply(state.direction));
+
+        for direction in straight.into_iter().chain(turns) {
+            let next = state.next(direction);
+            if let Some(heat_loss) = grid.get(next.x, next.y) {
+                buffer.push((next, heat_loss));
+            }
+        }
+    }
+
+    /// The crucible hasn't moved yet, so it starts out facing every direction with nothing
+    /// travelled so far, letting it go straight whichever way it leaves.
+    fn seeds(&self) -> Vec<State> {
+        let (x, y) = self.start;
+        [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+        .into_iter()
+        .map(|direction| State {
+            x,
+            y,
+            direction,
+            straight: 0,
+        })
+        .collect()
+    }
+
+    fn end(&self, grid: &Grid) -> (isize, isize) {
+        self.end
+            .unwrap_or((grid.width as isize - 1, grid.height as isize - 1))
+    }
+
+    /// The route losing the least heat from `start` to `end`.
+    pub fn solve(&self, grid: &Grid) -> Result<Route, NoRoute> {
+        self.solve_with(grid, Backend::AStar).0
+    }
+
+    /// Like `solve`, but with the search backend picked and how much work it did returned too.
+    pub fn solve_with(&self, grid: &Grid, backend: Backend) -> (Result<Route, NoRoute>, Stats) {
+        let end = self.end(grid);
+        let successors = |state: &State, buffer: &mut Vec<_>| self.successors(grid, state, buffer);
+        let success =
+            |state: &State| (state.x, state.y) == end && state.straight >= self.min_straight;
+
+        let Search { path, stats } = match backend {
+            Backend::Dijkstra => search::dijkstra(self.seeds(), successors, success),
+            Backend::AStar => {
+                // Every block loses at least this much heat, so the distance left times it can
+                // never overestimate
+                let min_heat_loss = grid.tiles.iter().min().copied().unwrap_or(0);
+                let heuristic = |state: &State| {
+                    let distance =
+                        (end.0 - state.x).unsigned_abs() + (end.1 - state.y).unsigned_abs();
+                    distance as u32 * min_heat_loss
+                };
+                search::astar(self.seeds(), successors, heuristic, success)
+            }
+        };
+
+        let route = path
+            .map(|path| {
+                // The first state is the seed, which is where the crucible starts and loses no
+                // heat
+                let steps = path
+                    .nodes
+                    .into_iter()
+                    .skip(1)
+                    .map(|state| (state, grid.get(state.x, state.y).unwrap()))
+                    .collect();
+                Route { steps }
+            })
+            .ok_or(NoRoute {
+                start: self.start,
+                end,
+            });
+
+        (route, stats)
+    }
+
+    pub fn min_heat_loss(&self, grid: &Grid) -> Result<u32, NoRoute> {
+        self.solve(grid).map(|route| route.heat_loss())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// Every state the crucible moves into after leaving the start, along with the heat lost
+    /// entering that block.
+    pub steps: Vec<(State, u32)>,
+}
+
+impl Route {
+    pub fn heat_loss(&self) -> u32 {
+        self.steps.iter().map(|(_, heat_loss)| heat_loss).sum()
+    }
+
+    /// Draws the grid with the direction the crucible moved in on top of every block it went
+    /// through, like the puzzle does.
+    pub fn render(&self, grid: &Grid) -> String {
+        let mut chars = grid
+            .tiles
+            .iter()
+            .map(|heat_loss| char::from_digit(*heat_loss, 10).unwrap())
+            .collect::<Vec<_>>();
+
+        for (state, _) in &self.steps {
+            chars[state.y as usize * grid.width + state.x as usize] = state.direction.arrow();
+        }
+
+        chars
+            .chunks(grid.width)
+            .flat_map(|row| row.iter().copied().chain(['\n']))
+            .collect()
+    }
+}
+
+/// Returned when the crucible can't get to the end without breaking its movement rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoRoute {
+    pub start: (isize, isize),
+    pub end: (isize, isize),
+}
+
+impl fmt::Display for NoRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "No route from ({}, {}) to ({}, {})",
+            self.start.0, self.start.1, self.end.0, self.end.1
+        )
+    }
+}
+
+impl std::error::Error for NoRoute {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533
+";
+
+    #[test]
+    fn crucibles() {
+        let grid = Grid::parse(EXAMPLE);
+        assert_eq!(Crucible::regular().min_heat_loss(&grid), Ok(102));
+        assert_eq!(Crucible::ultra().min_heat_loss(&grid), Ok(94));
+
+        let crucible = Crucible::regular();
+        let (dijkstra, dijkstra_stats) = crucible.solve_with(&grid, Backend::Dijkstra);
+        let (astar, astar_stats) = crucible.solve_with(&grid, Backend::AStar);
+        assert_eq!(dijkstra.unwrap().heat_loss(), astar.unwrap().heat_loss());
+        assert!(astar_stats.expanded <= dijkstra_stats.expanded);
+
+        let grid =
+            Grid::parse("111111111111\n999999999991\n999999999991\n999999999991\n999999999991\n");
+        assert_eq!(Crucible::ultra().min_heat_loss(&grid), Ok(71));
+
+        let grid = Grid::parse("123\n");
+        assert_eq!(
+            Crucible::ultra().min_heat_loss(&grid),
+            Err(NoRoute {
+                start: (0, 0),
+                end: (2, 0)
+            })
+        );
+    }
+
+    #[test]
+    fn route() {
+        let grid = Grid::parse(EXAMPLE);
+        let route = Crucible::regular().solve(&grid).unwrap();
+        assert_eq!(route.heat_loss(), 102);
+        assert_eq!(route.steps.last().unwrap().1, 3);
+        assert_eq!(
+            route.render(&grid),
+            "\
+2>>34^>>>1323
+32v>>>35v5623
+32552456v>>54
+3446585845v52
+4546657867v>6
+14385987984v4
+44578769877v6
+36378779796v>
+465496798688v
+456467998645v
+12246868655<v
+25465488877v5
+43226746555v>
+"
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use day18::{DigPlan, Encoding};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let encoding = match args.next().as_deref() {
+        Some("columns") | None => Encoding::Columns,
+        Some("hex") => Encoding::Hex,
+        Some(other) => panic!("Unknown encoding '{other}'"),
+    };
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+
+    let plan = DigPlan::parse(&input, encoding).unwrap();
+    print!("{}", plan.to_svg(1000.0));
+}
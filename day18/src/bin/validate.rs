@@ -0,0 +1,22 @@
+use day18::{DigPlan, Encoding};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let encoding = match args.next().as_deref() {
+        Some("columns") | None => Encoding::Columns,
+        Some("hex") => Encoding::Hex,
+        Some(other) => panic!("Unknown encoding '{other}'"),
+    };
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+
+    let plan = DigPlan::parse(&input, encoding).unwrap();
+    let report = plan.validate();
+    print!("{report}");
+    match plan.volume() {
+        Ok(volume) => println!("Volume: {volume}"),
+        Err(err) => println!("{err}"),
+    }
+}
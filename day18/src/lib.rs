@@ -0,0 +1,321 @@
+use anyhow::Context;
+use std::collections::VecDeque;
+use std::fmt;
+
+pub mod svg;
+pub mod validate;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Right,
+    Left,
+}
+
+impl Direction {
+    pub fn from_char(char: char) -> Option<Self> {
+        match char {
+            'U' => Some(Self::Up),
+            'D' => Some(Self::Down),
+            'R' => Some(Self::Right),
+            'L' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    /// The last digit of the hex code, which is what the elves actually meant.
+    pub fn from_hex_digit(char: char) -> Option<Self> {
+        match char {
+            '0' => Some(Self::Right),
+            '1' => Some(Self::Down),
+            '2' => Some(Self::Left),
+            '3' => Some(Self::Up),
+            _ => None,
+        }
+    }
+
+    pub fn dx(&self) -> i64 {
+        match self {
+            Self::Up => 0,
+            Self::Down => 0,
+            Self::Right => 1,
+            Self::Left => -1,
+        }
+    }
+
+    pub fn dy(&self) -> i64 {
+        match self {
+            Self::Up => -1,
+            Self::Down => 1,
+            Self::Right => 0,
+            Self::Left => 0,
+        }
+    }
+}
+
+/// Which columns of the dig plan the instructions are read from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Encoding {
+    /// The direction and length columns, like `R 6`.
+    Columns,
+    /// The hex code, where the first five digits are the length and the last one the direction.
+    Hex,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Colour {
+    /// Parses a colour like `#70c710`.
+    pub fn parse(code: &str) -> anyhow::Result<Self> {
+        let code = code
+            .strip_prefix('#')
+            .filter(|code| code.len() == 6 && code.chars().all(|c| c.is_ascii_hexdigit()))
+            .with_context(|| format!("Invalid colour '{code}'"))?;
+        let channel = |i: usize| u8::from_str_radix(&code[i..i + 2], 16);
+
+        Ok(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        })
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Instruction {
+    pub direction: Direction,
+    pub length: u64,
+    /// The hex code read as a colour, kept even when it was also decoded into the direction and
+    /// length. Optional with the column encoding.
+    pub colour: Option<Colour>,
+}
+
+impl Instruction {
+    pub fn parse(line: &str, encoding: Encoding) -> anyhow::Result<Self> {
+        let mut parts = line.split_whitespace();
+        let (direction, length) = (parts.next(), parts.next());
+        let code = parts
+            .next()
+            .map(|code| {
+                code.strip_prefix('(')
+                    .and_then(|code| code.strip_suffix(')'))
+                    .with_context(|| format!("Invalid hex code '{code}'"))
+            })
+            .transpose()?;
+        let colour = code.map(Colour::parse).transpose()?;
+
+        let (direction, length) = match encoding {
+            Encoding::Columns => {
+                let direction = direction
+                    .and_then(|direction| direction.chars().next())
+                    .and_then(Direction::from_char)
+                    .context("Invalid direction")?;
+                let length = length
+                    .context("Missing length")?
+                    .parse()
+                    .context("Invalid length")?;
+                (direction, length)
+            }
+            Encoding::Hex => {
+                // Already checked to be six hex digits when parsing the colour
+                let code = &code.context("Missing hex code")?[1..];
+                let length = u64::from_str_radix(&code[..5], 16)?;
+                let direction = code[5..]
+                    .chars()
+                    .next()
+                    .and_then(Direction::from_hex_digit)
+                    .context("Invalid direction")?;
+                (direction, length)
+            }
+        };
+
+        Ok(Self {
+            direction,
+            length,
+            colour,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DigPlan {
+    pub instructions: Vec<Instruction>,
+}
+
+impl DigPlan {
+    pub fn parse(input: &str, encoding: Encoding) -> anyhow::Result<Self> {
+        let instructions = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                Instruction::parse(line, encoding)
+                    .with_context(|| format!("Invalid instruction on line {}", i + 1))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self { instructions })
+    }
+
+    /// Corners of the trench, starting at the origin.
+    pub fn vertices(&self) -> Vec<(i64, i64)> {
+        let (mut x, mut y) = (0, 0);
+        let mut vertices = vec![];
+
+        for instruction in &self.instructions {
+            vertices.push((x, y));
+            x += instruction.direction.dx() * instruction.length as i64;
+            y += instruction.direction.dy() * instruction.length as i64;
+        }
+
+        vertices
+    }
+
+    /// Where the trench ends up, which is back at the origin for closed plans.
+    pub fn end(&self) -> (i64, i64) {
+        self.instructions
+            .iter()
+            .fold((0, 0), |(x, y), instruction| {
+                (
+                    x + instruction.direction.dx() * instruction.length as i64,
+                    y + instruction.direction.dy() * instruction.length as i64,
+                )
+            })
+    }
+
+    pub fn perimeter(&self) -> u64 {
+        self.instructions.iter().map(|ins| ins.length).sum()
+    }
+
+    /// The shoelace formula, which is positive when the vertices go clockwise.
+    fn twice_signed_area(&self) -> i64 {
+        let vertices = self.vertices();
+        vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+            .sum()
+    }
+
+    /// Cubic meters of lava the lagoon holds, trench included. Fails if the plan doesn't pass
+    /// `validate`, since the area would be meaningless.
+    ///
+    /// The shoelace formula gives the area of the polygon going through the middle of the trench
+    /// blocks, which misses the outer half of every trench block plus a quarter for each of the
+    /// four more outer corners than inner ones, hence the `perimeter / 2 + 1`.
+    pub fn volume(&self) -> anyhow::Result<u64> {
+        let report = self.validate();
+        if let Some(problem) = report.problems.first() {
+            anyhow::bail!("Invalid dig plan: {problem}");
+        }
+
+        Ok(self.twice_signed_area().unsigned_abs() / 2 + self.perimeter() / 2 + 1)
+    }
+
+    /// Like `volume`, but by digging out every block and flood filling the outside. Only meant
+    /// for checking small plans, so it gives up once there are more than `max_blocks` blocks to
+    /// look at.
+    pub fn flood_fill_volume(&self, max_blocks: usize) -> Option<u64> {
+        let mut trench = vec![];
+        let (mut x, mut y) = (0i64, 0i64);
+        for instruction in &self.instructions {
+            for _ in 0..instruction.length {
+                x += instruction.direction.dx();
+                y += instruction.direction.dy();
+                trench.push((x, y));
+            }
+        }
+
+        // One block of padding all around, so the outside is connected
+        let min_x = trench.iter().map(|(x, _)| *x).min()? - 1;
+        let min_y = trench.iter().map(|(_, y)| *y).min()? - 1;
+        let width = (trench.iter().map(|(x, _)| *x).max()? - min_x + 2) as usize;
+        let height = (trench.iter().map(|(_, y)| *y).max()? - min_y + 2) as usize;
+        if width.saturating_mul(height) > max_blocks {
+            return None;
+        }
+
+        let mut dug = vec![false; width * height];
+        for (x, y) in trench {
+            dug[(y - min_y) as usize * width + (x - min_x) as usize] = true;
+        }
+
+        let mut outside = vec![false; width * height];
+        let mut queue = VecDeque::from([(0, 0)]);
+        outside[0] = true;
+        while let Some((x, y)) = queue.pop_front() {
+            for direction in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Right,
+                Direction::Left,
+            ] {
+                let (nx, ny) = (x + direction.dx(), y + direction.dy());
+                if nx < 0 || ny < 0 || nx >= width as i64 || ny >= height as i64 {
+                    continue;
+                }
+
+                let index = ny as usize * width + nx as usize;
+                if !dug[index] && !outside[index] {
+                    outside[index] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        Some(outside.iter().filter(|outside| !**outside).count() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)
+";
+
+    #[test]
+    fn volume() {
+        let plan = DigPlan::parse(EXAMPLE, Encoding::Columns).unwrap();
+        assert_eq!(plan.volume().unwrap(), 62);
+        assert_eq!(plan.flood_fill_volume(1000), Some(62));
+        assert_eq!(plan.flood_fill_volume(10), None);
+
+        let plan = DigPlan::parse(EXAMPLE, Encoding::Hex).unwrap();
+        assert_eq!(plan.volume().unwrap(), 952408144115);
+        assert_eq!(plan.instructions[0].colour.unwrap().to_string(), "#70c710");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = DigPlan::parse("R 6 (#70c710)\nX 5 (#0dc571)\n", Encoding::Columns).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid instruction on line 2");
+
+        assert!(DigPlan::parse("R 6 (#70c714)\n", Encoding::Hex).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::DigPlan;
+use std::fmt::Write;
+
+const MARGIN: f64 = 10.0;
+const FILL: &str = "#d8c7a5";
+const DEFAULT_STROKE: &str = "#000000";
+
+impl DigPlan {
+    /// Draws the trench as an SVG image that fits in a `size` by `size` square, keeping the aspect
+    /// ratio. Every edge is stroked with the colour from its instruction, or black if it didn't
+    /// have one, and the interior is filled if the trench ends up back at the start.
+    ///
+    /// Edges go through the middle of the trench blocks, so the picture matches the polygon the
+    /// volume is worked out from.
+    pub fn to_svg(&self, size: f64) -> String {
+        let end = self.end();
+        let mut vertices = self.vertices();
+        vertices.push(end);
+        let min_x = vertices.iter().map(|(x, _)| *x).min().unwrap_or(0);
+        let min_y = vertices.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let max_x = vertices.iter().map(|(x, _)| *x).max().unwrap_or(0);
+        let max_y = vertices.iter().map(|(_, y)| *y).max().unwrap_or(0);
+
+        let span = (max_x - min_x).max(max_y - min_y).max(1) as f64;
+        let scale = (size - 2.0 * MARGIN) / span;
+        let point = |(x, y): (i64, i64)| {
+            (
+                MARGIN + (x - min_x) as f64 * scale,
+                MARGIN + (y - min_y) as f64 * scale,
+            )
+        };
+
+        let width = (max_x - min_x) as f64 * scale + 2.0 * MARGIN;
+        let height = (max_y - min_y) as f64 * scale + 2.0 * MARGIN;
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.2} {height:.2}">"#
+        )
+        .unwrap();
+
+        let closed = end == (0, 0) && !self.instructions.is_empty();
+        let points = vertices[..vertices.len() - 1]
+            .iter()
+            .map(|vertex| {
+                let (x, y) = point(*vertex);
+                format!("{x:.2},{y:.2}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        if closed {
+            writeln!(
+                svg,
+                r#"  <polygon points="{points}" fill="{FILL}" stroke="none"/>"#
+            )
+            .unwrap();
+        }
+
+        for (instruction, edge) in self.instructions.iter().zip(vertices.windows(2)) {
+            let (start, end) = (&edge[0], &edge[1]);
+            let ((x1, y1), (x2, y2)) = (point(*start), point(*end));
+            let stroke = instruction
+                .colour
+                .map_or(DEFAULT_STROKE.to_string(), |colour| colour.to_string());
+            writeln!(
+                svg,
+                r#"  <line x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="{stroke}" stroke-width="2" stroke-linecap="square"/>"#
+            )
+            .unwrap();
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Encoding;
+
+    use super::*;
+
+    #[test]
+    fn svg() {
+        let plan = DigPlan::parse(
+            "R 2 (#ff0000)\nD 2 (#00ff00)\nL 2 (#0000ff)\nU 2\n",
+            Encoding::Columns,
+        )
+        .unwrap();
+        let svg = plan.to_svg(120.0);
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="120" height="120""#)
+        );
+        assert!(svg
+            .contains(r#"<polygon points="10.00,10.00 110.00,10.00 110.00,110.00 10.00,110.00""#));
+        assert!(svg.contains(r##"x1="10.00" y1="10.00" x2="110.00" y2="10.00" stroke="#ff0000""##));
+        assert!(svg.contains(r##"x1="10.00" y1="110.00" x2="10.00" y2="10.00" stroke="#000000""##));
+        assert_eq!(svg.matches("<line").count(), 4);
+    }
+
+    #[test]
+    fn open_plan() {
+        let plan = DigPlan::parse("R 2\nD 2\nL 2\n", Encoding::Columns).unwrap();
+        let svg = plan.to_svg(120.0);
+
+        assert!(!svg.contains("<polygon"));
+        assert!(svg.contains(r#"x1="110.00" y1="110.00" x2="10.00" y2="110.00""#));
+        assert!(!svg.contains(r#"x2="10.00" y2="10.00""#));
+        assert_eq!(svg.matches("<line").count(), 3);
+
+        // The end of the last edge counts towards the size of the picture too
+        let plan = DigPlan::parse("R 2\nD 4\n", Encoding::Columns).unwrap();
+        assert!(plan.to_svg(120.0).contains(r#"width="70" height="120""#));
+    }
+}
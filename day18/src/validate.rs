@@ -0,0 +1,222 @@
+use crate::DigPlan;
+use std::fmt;
+
+/// Which way the trench goes around the lagoon, as seen with `U` pointing up.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Something that stops a dig plan from describing a simple polygon. Instructions are numbered
+/// from 0.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Problem {
+    Empty,
+    /// The trench ends somewhere other than where it started.
+    NotClosed {
+        end: (i64, i64),
+    },
+    ZeroLength {
+        instruction: usize,
+    },
+    /// Two edges cross or touch somewhere other than the corner they share.
+    Crossing {
+        first: usize,
+        second: usize,
+    },
+    /// Two edges run along each other for at least a block, including going back the way the
+    /// previous instruction came.
+    Overlapping {
+        first: usize,
+        second: usize,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Empty => write!(f, "The plan has no instructions"),
+            Problem::NotClosed { end: (x, y) } => {
+                write!(f, "The trench ends at ({x}, {y}) instead of the start")
+            }
+            Problem::ZeroLength { instruction } => {
+                write!(f, "Instruction #{} doesn't move", instruction + 1)
+            }
+            Problem::Crossing { first, second } => {
+                write!(f, "Instructions #{} and #{} cross", first + 1, second + 1)
+            }
+            Problem::Overlapping { first, second } => {
+                write!(f, "Instructions #{} and #{} overlap", first + 1, second + 1)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub problems: Vec<Problem>,
+    /// Only known for closed plans that enclose something.
+    pub winding: Option<Winding>,
+}
+
+impl Report {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for problem in &self.problems {
+            writeln!(f, "{problem}")?;
+        }
+
+        match self.winding {
+            Some(Winding::Clockwise) => writeln!(f, "The trench goes clockwise"),
+            Some(Winding::CounterClockwise) => writeln!(f, "The trench goes counter-clockwise"),
+            None => writeln!(f, "The trench doesn't go around anything"),
+        }
+    }
+}
+
+/// An axis-aligned edge with its coordinates sorted, so the ranges are easy to compare.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    instruction: usize,
+    x: (i64, i64),
+    y: (i64, i64),
+}
+
+impl Edge {
+    fn new(instruction: usize, (x1, y1): (i64, i64), (x2, y2): (i64, i64)) -> Self {
+        Self {
+            instruction,
+            x: (x1.min(x2), x1.max(x2)),
+            y: (y1.min(y2), y1.max(y2)),
+        }
+    }
+
+    /// How far the edges run along each other, or `None` if they don't meet at all. Edges that
+    /// meet in a single point share 0 blocks.
+    fn shared(&self, other: &Edge) -> Option<i64> {
+        let x = self.x.1.min(other.x.1) - self.x.0.max(other.x.0);
+        let y = self.y.1.min(other.y.1) - self.y.0.max(other.y.0);
+        (x >= 0 && y >= 0).then_some(x.max(y))
+    }
+}
+
+impl DigPlan {
+    /// Checks that the plan is a closed loop with no zero length moves that never crosses or
+    /// touches itself, which is what `volume` needs to be right.
+    pub fn validate(&self) -> Report {
+        let mut problems = vec![];
+        if self.instructions.is_empty() {
+            problems.push(Problem::Empty);
+        }
+
+        let vertices = self.vertices();
+        let end = self.end();
+        let closed = end == (0, 0);
+        if !closed {
+            problems.push(Problem::NotClosed { end });
+        }
+
+        let mut edges = vec![];
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            if instruction.length == 0 {
+                problems.push(Problem::ZeroLength { instruction: i });
+                continue;
+            }
+
+            let next = vertices.get(i + 1).copied().unwrap_or(end);
+            edges.push(Edge::new(i, vertices[i], next));
+        }
+
+        for i in 0..edges.len() {
+            for j in i + 1..edges.len() {
+                let adjacent = j == i + 1 || (closed && i == 0 && j == edges.len() - 1);
+                let (first, second) = (edges[i].instruction, edges[j].instruction);
+
+                match edges[i].shared(&edges[j]) {
+                    Some(0) if !adjacent => problems.push(Problem::Crossing { first, second }),
+                    Some(1..) => problems.push(Problem::Overlapping { first, second }),
+                    _ => {}
+                }
+            }
+        }
+
+        let twice_area = self.twice_signed_area();
+        let winding = match twice_area {
+            _ if !closed => None,
+            1.. => Some(Winding::Clockwise),
+            ..=-1 => Some(Winding::CounterClockwise),
+            0 => None,
+        };
+
+        Report { problems, winding }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Encoding;
+
+    use super::*;
+
+    fn validate(input: &str) -> Report {
+        DigPlan::parse(input, Encoding::Columns).unwrap().validate()
+    }
+
+    #[test]
+    fn problems() {
+        let report = validate("R 2\nD 2\nL 2\nU 2\n");
+        assert!(report.is_valid());
+        assert_eq!(report.winding, Some(Winding::Clockwise));
+        assert_eq!(
+            validate("D 2\nR 2\nU 2\nL 2\n").winding,
+            Some(Winding::CounterClockwise)
+        );
+
+        assert_eq!(
+            validate("R 2\nD 0\nD 2\nL 2\nU 1\n").problems,
+            [
+                Problem::NotClosed { end: (0, 1) },
+                Problem::ZeroLength { instruction: 1 }
+            ]
+        );
+
+        // A figure eight, where the third edge crosses the first one
+        assert_eq!(
+            validate("R 2\nD 1\nL 1\nU 2\nL 1\nD 1\n").problems,
+            [Problem::Crossing {
+                first: 0,
+                second: 3
+            }]
+        );
+
+        // Going back over the first edge, which also leaves the next one starting in its middle
+        assert_eq!(
+            validate("R 2\nL 1\nD 1\nL 1\nU 1\n").problems,
+            [
+                Problem::Overlapping {
+                    first: 0,
+                    second: 1
+                },
+                Problem::Crossing {
+                    first: 0,
+                    second: 2
+                }
+            ]
+        );
+
+        let err = DigPlan::parse("R 2\nD 2\nL 2\n", Encoding::Columns)
+            .unwrap()
+            .volume()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid dig plan: The trench ends at (0, 2) instead of the start"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use day19::{compile::Program, Schema, Workflows};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let input = match args.next() {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+    let categories = args.next();
+    let schema = match &categories {
+        Some(categories) => Schema::parse(categories).unwrap(),
+        None => Schema::xmas(),
+    };
+    let (workflows, _) = input.split_once("\n\n").unwrap_or((&input, ""));
+    let workflows = Workflows::parse(workflows, &schema).unwrap();
+
+    let program = match Program::compile(&workflows, "in") {
+        Ok(program) => program,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+    println!("{} nodes", program.nodes.len());
+
+    let analysis = program.analyze(&schema);
+    for warning in &analysis.warnings {
+        println!("{warning}");
+    }
+
+    println!("{} accepted regions:", analysis.accepted.len());
+    for region in &analysis.accepted {
+        let ranges = schema
+            .categories()
+            .map(|category| {
+                let range = region.get(category);
+                format!(
+                    "{}={}..={}",
+                    schema.name(category),
+                    range.start(),
+                    range.end()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("  {ranges}");
+    }
+    match analysis.accepted_count() {
+        Some(count) => println!("{count} parts accepted"),
+        None => println!("Too many parts accepted to count"),
+    }
+}
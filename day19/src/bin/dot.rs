@@ -0,0 +1,25 @@
+use day19::{MachinePart, Schema, Workflows};
+
+/// Prints the workflows as a Graphviz graph, highlighting the route of the part given as the
+/// first argument if there is one, like `{x=787,m=2655,a=1222,s=2876}`.
+fn main() {
+    let input = include_str!("./input.txt");
+    let schema = Schema::xmas();
+    let (workflows, _) = input.split_once("\n\n").unwrap_or((input, ""));
+    let workflows = Workflows::parse(workflows, &schema).unwrap();
+
+    let route = std::env::args().nth(1).map(|part| {
+        let part = MachinePart::parse(&part, &schema).unwrap();
+        let route = workflows.route("in", &part).unwrap();
+        let steps = route
+            .steps
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        let outcome = if route.accepted { "A" } else { "R" };
+        eprintln!("{} -> {outcome}", steps.join(" -> "));
+        route
+    });
+
+    print!("{}", workflows.to_dot(&schema, route.as_ref()));
+}
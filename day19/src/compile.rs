@@ -0,0 +1,500 @@
+//! Turns the workflows into a graph of single comparisons, which makes it possible to work out
+//! which parts end up accepted without going through them one at a time.
+
+use crate::{Action, Category, MachinePart, RuleCondition, Schema, Workflows};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Index into `Program::nodes`.
+pub type NodeId = usize;
+
+pub const ACCEPT: NodeId = 0;
+pub const REJECT: NodeId = 1;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Node<'a> {
+    Accept,
+    Reject,
+    /// A single rule, going to `then` if the part matches it and to `otherwise` if it doesn't.
+    Test {
+        workflow: &'a str,
+        rule: usize,
+        category: Category,
+        condition: RuleCondition,
+        num: u32,
+        then: NodeId,
+        otherwise: NodeId,
+    },
+}
+
+/// A box of parts, with a range of ratings for each category.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Region {
+    pub ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl Region {
+    /// Every part the schema allows.
+    pub fn full(schema: &Schema) -> Self {
+        Self {
+            ranges: schema
+                .categories()
+                .map(|category| schema.domain(category).clone())
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, category: Category) -> &RangeInclusive<u32> {
+        &self.ranges[category.0]
+    }
+
+    pub fn contains(&self, part: &MachinePart) -> bool {
+        self.ranges
+            .iter()
+            .zip(&part.ratings)
+            .all(|(range, rating)| range.contains(rating))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().any(|range| range.is_empty())
+    }
+
+    /// Parts in the region, or `None` if there are too many to fit in a `u128`.
+    pub fn count(&self) -> Option<u128> {
+        self.ranges.iter().try_fold(1u128, |count, range| {
+            let len = if range.is_empty() {
+                0
+            } else {
+                (*range.end() - *range.start()) as u128 + 1
+            };
+            count.checked_mul(len)
+        })
+    }
+
+    /// Splits the region into the boxes matching the condition and the ones that don't, leaving
+    /// out empty ones. `!=` and `=` can cut a range in two, so there are at most two of each.
+    fn split(
+        &self,
+        category: Category,
+        condition: RuleCondition,
+        num: u32,
+    ) -> (Vec<Region>, Vec<Region>) {
+        let range = self.get(category);
+        let (start, end) = (*range.start(), *range.end());
+        let (matching, rest) = condition.ranges(num);
+
+        let regions = |ranges: Vec<RangeInclusive<u32>>| {
+            ranges
+                .into_iter()
+                .map(|r| start.max(*r.start())..=end.min(*r.end()))
+                .filter(|r| !r.is_empty())
+                .map(|r| {
+                    let mut region = self.clone();
+                    region.ranges[category.0] = r;
+                    region
+                })
+                .collect()
+        };
+        (regions(matching), regions(rest))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CompileError {
+    UnknownEntry(String),
+    UndefinedTarget {
+        workflow: String,
+        target: String,
+    },
+    /// Workflows that send parts to each other in a circle, with the first one repeated at the
+    /// end.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::UnknownEntry(name) => write!(f, "There's no workflow '{name}'"),
+            CompileError::UndefinedTarget { workflow, target } => write!(
+                f,
+                "Workflow '{workflow}' sends parts to '{target}', which doesn't exist"
+            ),
+            CompileError::Cycle(workflows) => {
+                write!(f, "Workflows loop: {}", workflows.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// Things that don't stop the workflows from working, but probably aren't what was meant.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Warning<'a> {
+    /// No part can ever get to the workflow.
+    UnreachableWorkflow(&'a str),
+    /// No part that gets to the rule can ever match it.
+    DeadRule { workflow: &'a str, rule: usize },
+}
+
+impl fmt::Display for Warning<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::UnreachableWorkflow(name) => write!(f, "Workflow '{name}' is unreachable"),
+            Warning::DeadRule { workflow, rule } => {
+                write!(
+                    f,
+                    "Rule #{} of workflow '{workflow}' never matches",
+                    rule + 1
+                )
+            }
+        }
+    }
+}
+
+/// Which regions of parts get accepted, and what turned out to be dead along the way.
+#[derive(Debug, Clone)]
+pub struct Analysis<'a> {
+    /// Disjoint boxes that together make up every accepted part.
+    pub accepted: Vec<Region>,
+    pub warnings: Vec<Warning<'a>>,
+}
+
+impl Analysis<'_> {
+    /// `None` if there are too many accepted parts to fit in a `u128`.
+    pub fn accepted_count(&self) -> Option<u128> {
+        self.accepted
+            .iter()
+            .try_fold(0u128, |total, region| total.checked_add(region.count()?))
+    }
+}
+
+/// The workflows compiled into a DAG of single comparisons. Workflows that send parts to the
+/// same place share the nodes for it.
+#[derive(Debug, Clone)]
+pub struct Program<'a> {
+    pub nodes: Vec<Node<'a>>,
+    pub entry: NodeId,
+    /// First node of every workflow.
+    pub workflows: HashMap<&'a str, NodeId>,
+    /// Workflow names in the order they were declared.
+    order: Vec<&'a str>,
+    /// Workflows a part goes into by starting at the entry, or by following a test's `then`
+    /// (`true`) or `otherwise` (`false`) edge. There can be more than one, since a workflow
+    /// without rules shares the node of the one it sends everything to.
+    entered: HashMap<Option<(NodeId, bool)>, Vec<&'a str>>,
+}
+
+#[derive(Clone, Copy)]
+enum Visit {
+    InProgress,
+    Done(NodeId),
+}
+
+struct Compiler<'w, 'a> {
+    workflows: &'w Workflows<'a>,
+    nodes: Vec<Node<'a>>,
+    visits: HashMap<&'a str, Visit>,
+    stack: Vec<&'a str>,
+    /// Workflows a part goes into by being sent to each compiled workflow.
+    chains: HashMap<&'a str, Vec<&'a str>>,
+    entered: HashMap<Option<(NodeId, bool)>, Vec<&'a str>>,
+}
+
+impl<'a> Compiler<'_, 'a> {
+    /// Compiles a workflow after everything it sends parts to, so the nodes it points to
+    /// already exist.
+    fn workflow(&mut self, name: &'a str) -> Result<NodeId, CompileError> {
+        match self.visits.get(name) {
+            Some(Visit::Done(node)) => return Ok(*node),
+            Some(Visit::InProgress) => {
+                let start = self.stack.iter().position(|n| *n == name).unwrap();
+                let mut cycle = self.stack[start..]
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>();
+                cycle.push(name.to_string());
+                return Err(CompileError::Cycle(cycle));
+            }
+            None => {}
+        }
+
+        let workflow = self.workflows.get(name).unwrap();
+        self.visits.insert(name, Visit::InProgress);
+        self.stack.push(name);
+
+        let mut next = self.action(name, workflow.default)?;
+        let mut next_chain = self.chain(workflow.default);
+        for (i, rule) in workflow.rules.iter().enumerate().rev() {
+            let then = self.action(name, rule.action)?;
+            self.nodes.push(Node::Test {
+                workflow: name,
+                rule: i,
+                category: rule.category,
+                condition: rule.condition,
+                num: rule.num,
+                then,
+                otherwise: next,
+            });
+            next = self.nodes.len() - 1;
+
+            let then_chain = self.chain(rule.action);
+            self.entered.insert(Some((next, true)), then_chain);
+            self.entered
+                .insert(Some((next, false)), std::mem::take(&mut next_chain));
+        }
+
+        // Without rules, going into the workflow means going straight into the next one too
+        next_chain.insert(0, name);
+        self.chains.insert(name, next_chain);
+        self.stack.pop();
+        self.visits.insert(name, Visit::Done(next));
+        Ok(next)
+    }
+
+    fn action(&mut self, workflow: &'a str, action: Action<'a>) -> Result<NodeId, CompileError> {
+        match action {
+            Action::Accept => Ok(ACCEPT),
+            Action::Reject => Ok(REJECT),
+            Action::Redirect(target) if self.workflows.get(target).is_none() => {
+                Err(CompileError::UndefinedTarget {
+                    workflow: workflow.to_string(),
+                    target: target.to_string(),
+                })
+            }
+            Action::Redirect(target) => self.workflow(target),
+        }
+    }
+
+    /// Workflows a part goes into when the action is taken, which have to be compiled already.
+    fn chain(&self, action: Action<'a>) -> Vec<&'a str> {
+        match action {
+            Action::Redirect(target) => self.chains[target].clone(),
+            _ => vec![],
+        }
+    }
+}
+
+impl<'a> Program<'a> {
+    pub fn compile(workflows: &Workflows<'a>, entry: &'a str) -> Result<Self, CompileError> {
+        if workflows.get(entry).is_none() {
+            return Err(CompileError::UnknownEntry(entry.to_string()));
+        }
+
+        let mut compiler = Compiler {
+            workflows,
+            nodes: vec![Node::Accept, Node::Reject],
+            visits: HashMap::new(),
+            stack: vec![],
+            chains: HashMap::new(),
+            entered: HashMap::new(),
+        };
+        let entry_node = compiler.workflow(entry)?;
+        compiler
+            .entered
+            .insert(None, compiler.chains[entry].clone());
+
+        // Workflows no part can get to are still compiled, so mistakes in them are caught too
+        let mut compiled = HashMap::new();
+        for workflow in workflows.iter() {
+            compiled.insert(workflow.name, compiler.workflow(workflow.name)?);
+        }
+
+        Ok(Self {
+            nodes: compiler.nodes,
+            entry: entry_node,
+            workflows: compiled,
+            order: workflows.iter().map(|workflow| workflow.name).collect(),
+            entered: compiler.entered,
+        })
+    }
+
+    /// Follows every part the schema allows through the program at once, splitting them up at
+    /// every test.
+    pub fn analyze(&self, schema: &Schema) -> Analysis<'a> {
+        let mut accepted = vec![];
+        let mut reached = vec![false; self.nodes.len()];
+        let mut matched = vec![false; self.nodes.len()];
+
+        let mut reached_workflows = self.entered[&None].iter().copied().collect::<HashSet<_>>();
+        let mut enter = |edge, regions: &[Region]| {
+            if let Some(workflows) = self
+                .entered
+                .get(&Some(edge))
+                .filter(|_| !regions.is_empty())
+            {
+                reached_workflows.extend(workflows.iter().copied());
+            }
+        };
+
+        let mut stack = vec![(self.entry, Region::full(schema))];
+        while let Some((node, region)) = stack.pop() {
+            reached[node] = true;
+            match &self.nodes[node] {
+                Node::Accept => accepted.push(region),
+                Node::Reject => {}
+                Node::Test {
+                    category,
+                    condition,
+                    num,
+                    then,
+                    otherwise,
+                    ..
+                } => {
+                    let (matching, rest) = region.split(*category, *condition, *num);
+                    matched[node] |= !matching.is_empty();
+                    enter((node, true), &matching);
+                    enter((node, false), &rest);
+                    stack.extend(matching.into_iter().map(|region| (*then, region)));
+                    stack.extend(rest.into_iter().map(|region| (*otherwise, region)));
+                }
+            }
+        }
+
+        let mut warnings = self
+            .order
+            .iter()
+            .filter(|name| !reached_workflows.contains(*name))
+            .map(|name| Warning::UnreachableWorkflow(name))
+            .collect::<Vec<_>>();
+
+        // Rules in reachable workflows that no part gets to are shadowed by earlier ones
+        let mut dead = vec![];
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Node::Test { workflow, rule, .. } = node {
+                if reached_workflows.contains(workflow) && !(reached[i] && matched[i]) {
+                    let position = self.order.iter().position(|name| name == workflow);
+                    dead.push((position, *rule, *workflow));
+                }
+            }
+        }
+        dead.sort();
+        warnings.extend(
+            dead.into_iter()
+                .map(|(_, rule, workflow)| Warning::DeadRule { workflow, rule }),
+        );
+
+        Analysis { accepted, warnings }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, EXAMPLE};
+
+    #[test]
+    fn accepted_regions() {
+        let schema = Schema::xmas();
+        let (workflows, parts) = parse(EXAMPLE, &schema).unwrap();
+        let program = Program::compile(&workflows, "in").unwrap();
+        let analysis = program.analyze(&schema);
+
+        assert_eq!(analysis.accepted_count(), Some(167409079868000));
+        assert!(analysis.warnings.is_empty());
+        for part in parts {
+            let inside = analysis
+                .accepted
+                .iter()
+                .any(|region| region.contains(&part));
+            assert_eq!(inside, workflows.accepts("in", &part).unwrap());
+        }
+    }
+
+    #[test]
+    fn diagnostics() {
+        let schema = Schema::xmas();
+        let workflows =
+            Workflows::parse("in{x<10:a,R}\na{x>20:b,m<5:R,A}\nb{A}\nc{R}", &schema).unwrap();
+        let program = Program::compile(&workflows, "in").unwrap();
+        assert_eq!(
+            program.analyze(&schema).warnings,
+            [
+                Warning::UnreachableWorkflow("b"),
+                Warning::UnreachableWorkflow("c"),
+                Warning::DeadRule {
+                    workflow: "a",
+                    rule: 0
+                }
+            ]
+        );
+
+        // Shadowed by the first rule, and a workflow without rules only reached through it
+        let workflows = Workflows::parse(
+            "in{x<10:a,R}
+a{x>0:A,m<5:b,A}
+b{c}
+c{A}",
+            &schema,
+        )
+        .unwrap();
+        let program = Program::compile(&workflows, "in").unwrap();
+        assert_eq!(
+            program.analyze(&schema).warnings,
+            [
+                Warning::UnreachableWorkflow("b"),
+                Warning::UnreachableWorkflow("c"),
+                Warning::DeadRule {
+                    workflow: "a",
+                    rule: 1
+                }
+            ]
+        );
+
+        let workflows = Workflows::parse("in{x<10:a,R}\na{x>20:in,A}", &schema).unwrap();
+        assert_eq!(
+            Program::compile(&workflows, "in").unwrap_err(),
+            CompileError::Cycle(vec!["in".to_string(), "a".to_string(), "in".to_string()])
+        );
+
+        let workflows = Workflows::parse("in{A}\nz{x<10:a,R}", &schema).unwrap();
+        assert_eq!(
+            Program::compile(&workflows, "in").unwrap_err().to_string(),
+            "Workflow 'z' sends parts to 'a', which doesn't exist"
+        );
+    }
+
+    #[test]
+    fn custom_categories() {
+        let schema = Schema::parse("cool=0..=9,shiny=0..=9").unwrap();
+        let workflows = Workflows::parse(
+            "in{cool!=3:a,shiny>=5:A,R}\na{shiny<=2:R,cool=7:A,shiny<9:R,A}",
+            &schema,
+        )
+        .unwrap();
+        let analysis = Program::compile(&workflows, "in").unwrap().analyze(&schema);
+
+        let brute_force = (0..=9)
+            .flat_map(|cool| (0..=9).map(move |shiny| vec![cool, shiny]))
+            .map(|ratings| MachinePart { ratings })
+            .filter(|part| workflows.accepts("in", part).unwrap())
+            .count();
+        assert_eq!(analysis.accepted_count(), Some(brute_force as u128));
+        assert_eq!(brute_force, 5 + 7 + 8);
+        assert!(analysis.warnings.is_empty());
+    }
+
+    #[test]
+    fn huge_schemas() {
+        let categories = (0..11).map(|i| format!("c{i}")).collect::<Vec<_>>();
+        let schema = categories
+            .iter()
+            .fold(Schema::new(), |schema, name| schema.with(name, 1..=4000));
+        let workflows = Workflows::parse("in{A}", &schema).unwrap();
+        let analysis = Program::compile(&workflows, "in").unwrap().analyze(&schema);
+        assert_eq!(analysis.accepted_count(), None);
+
+        // Each half fits, but not both together
+        let schema =
+            Schema::parse("a=0..=4294967295,b=0..=4294967295,c=0..=4294967295,d=0..=4294967295")
+                .unwrap();
+        let workflows = Workflows::parse("in{a<2147483648:A,A}", &schema).unwrap();
+        let analysis = Program::compile(&workflows, "in").unwrap().analyze(&schema);
+        assert!(analysis
+            .accepted
+            .iter()
+            .all(|region| region.count().is_some()));
+        assert_eq!(analysis.accepted_count(), None);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Action, Route, Schema, Workflows};
+use std::fmt::Write;
+
+const HIGHLIGHT: &str = "#d62728";
+
+impl<'a> Workflows<'a> {
+    /// A Graphviz graph with a node for every workflow and an edge for every rule, going to
+    /// another workflow or to the `A` and `R` sinks. The edge for the default action is labelled
+    /// `else`.
+    ///
+    /// With a `route` from `Workflows::route`, the workflows the part went through and the rules
+    /// that sent it on are highlighted.
+    pub fn to_dot(&self, schema: &Schema, route: Option<&Route<'a>>) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph workflows {{").unwrap();
+        writeln!(dot, "  rankdir=LR;").unwrap();
+        writeln!(dot, "  node [shape=box, fontname=monospace];").unwrap();
+        writeln!(dot, "  edge [fontname=monospace];").unwrap();
+
+        let steps = route.map_or(&[][..], |route| &route.steps);
+        let outcome = route.map(|route| if route.accepted { "A" } else { "R" });
+        for (sink, colour) in [("A", "#2ca02c"), ("R", "#7f7f7f")] {
+            let width = if outcome == Some(sink) { 3 } else { 1 };
+            writeln!(
+                dot,
+                "  \"{sink}\" [shape=doublecircle, color=\"{colour}\", penwidth={width}];"
+            )
+            .unwrap();
+        }
+
+        for workflow in self.iter() {
+            let name = workflow.name;
+            let taken = steps
+                .iter()
+                .find(|(step, _)| *step == name)
+                .map(|(_, rule)| *rule);
+            let style = if taken.is_some() {
+                format!(" [color=\"{HIGHLIGHT}\", penwidth=3]")
+            } else {
+                String::new()
+            };
+            writeln!(dot, "  \"{name}\"{style};").unwrap();
+
+            let rules = workflow.rules.iter().map(|rule| {
+                let label = format!(
+                    "{}{}{}",
+                    schema.name(rule.category),
+                    rule.condition.as_str(),
+                    rule.num
+                );
+                (label, rule.action)
+            });
+            let edges = rules.chain([("else".to_string(), workflow.default)]);
+
+            for (i, (label, action)) in edges.enumerate() {
+                let target = match action {
+                    Action::Redirect(target) => target,
+                    Action::Accept => "A",
+                    Action::Reject => "R",
+                };
+                // `None` is the default action, which is the edge after the last rule
+                let highlighted =
+                    taken == Some(Some(i)) || (taken == Some(None) && i == workflow.rules.len());
+                let style = if highlighted {
+                    format!(", color=\"{HIGHLIGHT}\", fontcolor=\"{HIGHLIGHT}\", penwidth=3")
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    dot,
+                    "  \"{name}\" -> \"{target}\" [label=\"{label}\"{style}];"
+                )
+                .unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, EXAMPLE};
+
+    use super::*;
+
+    #[test]
+    fn highlighted_route() {
+        let schema = Schema::xmas();
+        let (workflows, parts) = parse(EXAMPLE, &schema).unwrap();
+        let route = workflows.route("in", &parts[0]).unwrap();
+        let dot = workflows.to_dot(&schema, Some(&route));
+
+        assert!(dot.contains("\"px\" -> \"qkq\" [label=\"a<2006\"];"));
+        assert!(dot.contains(&format!(
+            "\"in\" -> \"qqz\" [label=\"else\", color=\"{HIGHLIGHT}\""
+        )));
+        assert!(dot.contains(&format!(
+            "\"lnx\" -> \"A\" [label=\"m>1548\", color=\"{HIGHLIGHT}\""
+        )));
+        assert!(!dot.contains("\"lnx\" -> \"A\" [label=\"else\", color"));
+        assert!(dot.contains("\"A\" [shape=doublecircle, color=\"#2ca02c\", penwidth=3];"));
+        assert_eq!(dot.matches(" -> ").count(), 11 + 14);
+    }
+}
@@ -0,0 +1,453 @@
+use anyhow::Context;
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+pub mod compile;
+pub mod dot;
+
+/// Index of a category in a `Schema`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Category(pub usize);
+
+/// The categories parts are rated in, along with the ratings that are possible in each.
+#[derive(Debug, Clone, Default)]
+pub struct Schema<'a> {
+    names: Vec<&'a str>,
+    domains: Vec<RangeInclusive<u32>>,
+}
+
+impl<'a> Schema<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The categories from the puzzle, each rated from 1 to 4000.
+    pub fn xmas() -> Schema<'static> {
+        Schema::new()
+            .with("x", 1..=4000)
+            .with("m", 1..=4000)
+            .with("a", 1..=4000)
+            .with("s", 1..=4000)
+    }
+
+    pub fn with(mut self, name: &'a str, domain: RangeInclusive<u32>) -> Self {
+        self.names.push(name);
+        self.domains.push(domain);
+        self
+    }
+
+    /// Parses categories listed like `x=1..=4000,m=0..=9`.
+    pub fn parse(str: &'a str) -> anyhow::Result<Self> {
+        let mut schema = Self::new();
+        for category in str.split(',') {
+            let (name, domain) = category
+                .split_once('=')
+                .and_then(|(name, domain)| Some((name, domain.split_once("..=")?)))
+                .filter(|(name, _)| is_category_name(name))
+                .with_context(|| format!("Invalid category '{category}'"))?;
+            let (start, end) = domain
+                .0
+                .parse()
+                .ok()
+                .zip(domain.1.parse().ok())
+                .with_context(|| format!("Invalid range for category '{name}'"))?;
+
+            if schema.category(name).is_some() {
+                anyhow::bail!("Category '{name}' is listed twice");
+            }
+            schema = schema.with(name, start..=end);
+        }
+
+        Ok(schema)
+    }
+
+    pub fn category(&self, name: &str) -> Option<Category> {
+        self.names.iter().position(|n| *n == name).map(Category)
+    }
+
+    pub fn categories(&self) -> impl Iterator<Item = Category> {
+        (0..self.names.len()).map(Category)
+    }
+
+    pub fn name(&self, category: Category) -> &'a str {
+        self.names[category.0]
+    }
+
+    pub fn domain(&self, category: Category) -> &RangeInclusive<u32> {
+        &self.domains[category.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+fn is_category_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Ratings of a part, indexed by category.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MachinePart {
+    pub ratings: Vec<u32>,
+}
+
+impl MachinePart {
+    /// Parses a part like `{x=787,m=2655,a=1222,s=2876}`, which has to be rated in every
+    /// category of the schema.
+    pub fn parse(str: &str, schema: &Schema) -> anyhow::Result<Self> {
+        let ratings = str
+            .strip_prefix('{')
+            .and_then(|str| str.strip_suffix('}'))
+            .with_context(|| format!("Invalid part '{str}'"))?;
+
+        let mut parsed = vec![None; schema.len()];
+        for rating in ratings.split(',') {
+            let (name, num) = rating
+                .split_once('=')
+                .with_context(|| format!("Invalid rating '{rating}'"))?;
+            let category = schema
+                .category(name)
+                .with_context(|| format!("Unknown category '{name}'"))?;
+            let num = num
+                .parse()
+                .with_context(|| format!("Invalid rating '{rating}'"))?;
+            if !schema.domain(category).contains(&num) {
+                anyhow::bail!("Rating '{rating}' is out of range");
+            }
+
+            parsed[category.0] = Some(num);
+        }
+
+        let ratings = schema
+            .categories()
+            .map(|category| {
+                parsed[category.0]
+                    .with_context(|| format!("Missing rating for '{}'", schema.name(category)))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { ratings })
+    }
+
+    pub fn get(&self, category: Category) -> u32 {
+        self.ratings[category.0]
+    }
+
+    pub fn total(&self) -> u32 {
+        self.ratings.iter().sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RuleCondition {
+    Lower,
+    LowerOrEqual,
+    Higher,
+    HigherOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl RuleCondition {
+    /// Splits the condition off the start of `str`, returning it and the rest.
+    pub fn parse_prefix(str: &str) -> Option<(Self, &str)> {
+        // Two character operators first, so `<=` isn't read as `<`
+        [
+            ("<=", Self::LowerOrEqual),
+            (">=", Self::HigherOrEqual),
+            ("!=", Self::NotEqual),
+            ("<", Self::Lower),
+            (">", Self::Higher),
+            ("=", Self::Equal),
+        ]
+        .into_iter()
+        .find_map(|(op, condition)| Some((condition, str.strip_prefix(op)?)))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lower => "<",
+            Self::LowerOrEqual => "<=",
+            Self::Higher => ">",
+            Self::HigherOrEqual => ">=",
+            Self::Equal => "=",
+            Self::NotEqual => "!=",
+        }
+    }
+
+    pub fn matches(self, value: u32, num: u32) -> bool {
+        match self {
+            Self::Lower => value < num,
+            Self::LowerOrEqual => value <= num,
+            Self::Higher => value > num,
+            Self::HigherOrEqual => value >= num,
+            Self::Equal => value == num,
+            Self::NotEqual => value != num,
+        }
+    }
+
+    /// Every `u32` split into the ranges of values that match the condition and the ones that
+    /// don't. There are at most two of each, and none of them are empty.
+    pub fn ranges(self, num: u32) -> (Vec<RangeInclusive<u32>>, Vec<RangeInclusive<u32>>) {
+        let below = num.checked_sub(1).map(|below| 0..=below);
+        let above = num.checked_add(1).map(|above| above..=u32::MAX);
+
+        let (matching, rest) = match self {
+            Self::Lower => (vec![below], vec![Some(num..=u32::MAX)]),
+            Self::LowerOrEqual => (vec![Some(0..=num)], vec![above]),
+            Self::Higher => (vec![above], vec![Some(0..=num)]),
+            Self::HigherOrEqual => (vec![Some(num..=u32::MAX)], vec![below]),
+            Self::Equal => (vec![Some(num..=num)], vec![below, above]),
+            Self::NotEqual => (vec![below, above], vec![Some(num..=num)]),
+        };
+        (
+            matching.into_iter().flatten().collect(),
+            rest.into_iter().flatten().collect(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Action<'a> {
+    Redirect(&'a str),
+    Accept,
+    Reject,
+}
+
+impl<'a> Action<'a> {
+    pub fn parse(str: &'a str) -> Action<'a> {
+        match str {
+            "A" => Action::Accept,
+            "R" => Action::Reject,
+            _ => Action::Redirect(str),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule<'a> {
+    pub category: Category,
+    pub num: u32,
+    pub condition: RuleCondition,
+    pub action: Action<'a>,
+}
+
+impl<'a> Rule<'a> {
+    pub fn parse(str: &'a str, schema: &Schema) -> anyhow::Result<Self> {
+        let (test, action) = str.split_once(':').context("Missing action")?;
+
+        let split = test
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(test.len());
+        let (name, test) = test.split_at(split);
+        let category = schema
+            .category(name)
+            .with_context(|| format!("Unknown category '{name}'"))?;
+        let (condition, num) = RuleCondition::parse_prefix(test).context("Unknown condition")?;
+        let num = num.parse().context("Invalid number")?;
+
+        Ok(Self {
+            category,
+            num,
+            condition,
+            action: Action::parse(action),
+        })
+    }
+
+    pub fn does_match(&self, part: &MachinePart) -> bool {
+        self.condition.matches(part.get(self.category), self.num)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Workflow<'a> {
+    pub name: &'a str,
+    pub rules: Vec<Rule<'a>>,
+    pub default: Action<'a>,
+}
+
+impl<'a> Workflow<'a> {
+    pub fn parse(line: &'a str, schema: &Schema) -> anyhow::Result<Self> {
+        let (name, rules) = line
+            .strip_suffix('}')
+            .and_then(|line| line.split_once('{'))
+            .with_context(|| format!("Invalid workflow '{line}'"))?;
+
+        let mut rules = rules.split(',').collect::<Vec<_>>();
+        let default = Action::parse(rules.pop().unwrap());
+        let rules = rules
+            .into_iter()
+            .map(|rule| Rule::parse(rule, schema).with_context(|| format!("Invalid rule '{rule}'")))
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            name,
+            rules,
+            default,
+        })
+    }
+}
+
+/// Where a part went through the workflows, as the workflow and the index of the rule that
+/// matched, or `None` if none did and it took the default action.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Route<'a> {
+    pub steps: Vec<(&'a str, Option<usize>)>,
+    pub accepted: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Workflows<'a> {
+    workflows: HashMap<&'a str, Workflow<'a>>,
+    /// Workflow names in the order they were listed.
+    order: Vec<&'a str>,
+}
+
+impl<'a> Workflows<'a> {
+    pub fn parse(input: &'a str, schema: &Schema) -> anyhow::Result<Self> {
+        let mut workflows = HashMap::new();
+        let mut order = vec![];
+
+        for (i, line) in input.lines().enumerate() {
+            let workflow = Workflow::parse(line, schema)
+                .with_context(|| format!("Invalid workflow on line {}", i + 1))?;
+            let name = workflow.name;
+            if workflows.insert(name, workflow).is_some() {
+                anyhow::bail!("Workflow '{name}' is defined twice");
+            }
+            order.push(name);
+        }
+
+        Ok(Self { workflows, order })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Workflow<'a>> {
+        self.workflows.get(name)
+    }
+
+    /// Every workflow, in the order they were listed.
+    pub fn iter(&self) -> impl Iterator<Item = &Workflow<'a>> {
+        self.order.iter().map(|name| &self.workflows[name])
+    }
+
+    /// Sends a part through the workflows starting at `start`, failing if it ends up at a
+    /// workflow that doesn't exist or comes back to one it has already been through.
+    pub fn route(&self, start: &'a str, part: &MachinePart) -> anyhow::Result<Route<'a>> {
+        let mut steps = vec![];
+        let mut seen = HashSet::new();
+        let mut name = start;
+
+        loop {
+            let workflow = self
+                .get(name)
+                .with_context(|| format!("Unknown workflow '{name}'"))?;
+            if !seen.insert(name) {
+                anyhow::bail!("Part loops back to workflow '{name}'");
+            }
+
+            let rule = workflow.rules.iter().position(|rule| rule.does_match(part));
+            steps.push((name, rule));
+
+            match rule.map_or(workflow.default, |rule| workflow.rules[rule].action) {
+                Action::Accept => {
+                    return Ok(Route {
+                        steps,
+                        accepted: true,
+                    })
+                }
+                Action::Reject => {
+                    return Ok(Route {
+                        steps,
+                        accepted: false,
+                    })
+                }
+                Action::Redirect(next) => name = next,
+            }
+        }
+    }
+
+    pub fn accepts(&self, start: &'a str, part: &MachinePart) -> anyhow::Result<bool> {
+        Ok(self.route(start, part)?.accepted)
+    }
+}
+
+/// Splits the puzzle input into the workflows and the parts.
+pub fn parse<'a>(
+    input: &'a str,
+    schema: &Schema,
+) -> anyhow::Result<(Workflows<'a>, Vec<MachinePart>)> {
+    let (workflows, parts) = input
+        .split_once("\n\n")
+        .context("Missing empty line between the workflows and parts")?;
+
+    let workflows = Workflows::parse(workflows, schema)?;
+    let parts = parts
+        .lines()
+        .map(|part| MachinePart::parse(part, schema))
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok((workflows, parts))
+}
+
+#[cfg(test)]
+pub(crate) const EXAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes() {
+        let schema = Schema::xmas();
+        let (workflows, parts) = parse(EXAMPLE, &schema).unwrap();
+        let route = workflows.route("in", &parts[0]).unwrap();
+        assert_eq!(
+            route.steps,
+            [
+                ("in", None),
+                ("qqz", Some(0)),
+                ("qs", None),
+                ("lnx", Some(0))
+            ]
+        );
+        assert!(route.accepted);
+
+        let accepted = parts
+            .iter()
+            .filter(|part| workflows.accepts("in", part).unwrap())
+            .map(MachinePart::total)
+            .sum::<u32>();
+        assert_eq!(accepted, 19114);
+
+        let part = MachinePart::parse("{x=1,m=1,a=1,s=1}", &schema).unwrap();
+        let workflows = Workflows::parse("in{x<10:a,R}\na{b}", &schema).unwrap();
+        let err = workflows.route("in", &part).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown workflow 'b'");
+
+        let workflows = Workflows::parse("in{x<10:a,R}\na{in}", &schema).unwrap();
+        let err = workflows.route("in", &part).unwrap_err();
+        assert_eq!(err.to_string(), "Part loops back to workflow 'in'");
+    }
+}
@@ -0,0 +1,726 @@
+//! Works out when `rx` first gets a low pulse without pressing the button that many times.
+//!
+//! The network has to split into independent counters, one for each of the broadcaster's
+//! outputs. Every input of `rx` has to be either a module in one of the counters, or a gate: a
+//! conjunction that only sends to `rx`, with a single wire from each of the counters feeding it.
+//! Each counter is simulated until its state repeats, after which the presses where a gate sends
+//! `rx` a low pulse are found with the Chinese remainder theorem.
+//!
+//! Pulses are handled breadth first, so within a press the pulses at each depth come counter by
+//! counter, in the order the broadcaster sends to them. That's what makes it possible to tell
+//! what a gate remembers at any point by looking at each counter on its own.
+
+use crate::{Conjunction, ModuleId, Network, Pulse};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use util::cycle::Cycle;
+
+/// Presses to simulate before giving up on finding every counter's cycle.
+const MAX_PRESSES: u64 = 100_000;
+/// Combinations of presses to try solving with the CRT before giving up.
+const MAX_COMBINATIONS: usize = 10_000;
+
+/// Why the network can't be analyzed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Unsupported {
+    pub reason: String,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Can't analyze the network: {}", self.reason)
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+fn unsupported<T>(reason: String) -> Result<T, Unsupported> {
+    Err(Unsupported { reason })
+}
+
+/// A pulse sent out of a counter through its `exit`th exit.
+#[derive(Debug, Clone, Copy)]
+struct Output {
+    exit: usize,
+    depth: usize,
+    pulse: Pulse,
+}
+
+/// The pulses a counter sent out of it during a press, and what each exit last carried before.
+#[derive(Debug, Clone)]
+struct PressLog {
+    start: Vec<Pulse>,
+    outputs: Vec<Output>,
+}
+
+/// Modules only reachable from one of the broadcaster's outputs.
+#[derive(Debug, Clone)]
+pub struct Counter {
+    pub entry: ModuleId,
+    pub modules: Vec<ModuleId>,
+    /// Wires going from the counter to a gate or `rx`, as `(from, to)`.
+    pub exits: Vec<(ModuleId, ModuleId)>,
+    /// How the state of the modules, along with the last pulse through every exit, repeats.
+    pub cycle: Cycle,
+    /// Presses up to the end of the first period during which a high pulse left the counter.
+    pub fires: Vec<u64>,
+    /// Every press up to the end of the first period.
+    log: Vec<PressLog>,
+}
+
+impl Counter {
+    fn press(&self, press: u64) -> &PressLog {
+        &self.log[self.cycle.reduce(press - 1)]
+    }
+
+    /// The last pulse through `exit` with at most the given depth, or before the press if
+    /// there's no depth.
+    fn level(&self, press: u64, exit: usize, depth: Option<usize>) -> Pulse {
+        let log = self.press(press);
+        log.outputs
+            .iter()
+            .rev()
+            .find(|output| output.exit == exit && depth.is_some_and(|depth| output.depth <= depth))
+            .map_or(log.start[exit], |output| output.pulse)
+    }
+
+    fn sends_high(&self, press: u64, exit: usize, depth: usize) -> bool {
+        self.press(press).outputs.iter().any(|output| {
+            output.exit == exit && output.depth == depth && output.pulse == Pulse::High
+        })
+    }
+}
+
+/// A conjunction that only sends to `rx`, fed by several counters.
+#[derive(Debug, Clone)]
+pub struct Gate {
+    pub module: ModuleId,
+    /// The counters feeding the gate and the exit each one does it through, in the order of
+    /// `RxAnalysis::counters`.
+    pub inputs: Vec<(usize, usize)>,
+}
+
+impl Gate {
+    /// Whether input `k` allows the gate to send a low pulse when input `i` sends it a high one
+    /// at the given depth. Inputs before `i` have already sent all their pulses at that depth
+    /// by then, and the ones after `i` none of them.
+    fn allows(&self, counters: &[Counter], k: usize, i: usize, depth: usize, press: u64) -> bool {
+        let (counter, exit) = self.inputs[k];
+        let counter = &counters[counter];
+        match k.cmp(&i) {
+            std::cmp::Ordering::Equal => counter.sends_high(press, exit, depth),
+            std::cmp::Ordering::Less => counter.level(press, exit, Some(depth)) == Pulse::High,
+            std::cmp::Ordering::Greater => {
+                counter.level(press, exit, depth.checked_sub(1)) == Pulse::High
+            }
+        }
+    }
+
+    /// Depths at which any of the inputs ever sends a high pulse.
+    fn depths(&self, counters: &[Counter]) -> Vec<usize> {
+        let mut depths = self
+            .inputs
+            .iter()
+            .flat_map(|(counter, exit)| {
+                counters[*counter].log.iter().flat_map(move |log| {
+                    log.outputs
+                        .iter()
+                        .filter(move |output| output.exit == *exit && output.pulse == Pulse::High)
+                        .map(|output| output.depth)
+                })
+            })
+            .collect::<Vec<_>>();
+        depths.sort();
+        depths.dedup();
+        depths
+    }
+
+    fn sends_low(&self, counters: &[Counter], depths: &[usize], press: u64) -> bool {
+        (0..self.inputs.len()).any(|i| {
+            depths.iter().any(|depth| {
+                (0..self.inputs.len()).all(|k| self.allows(counters, k, i, *depth, press))
+            })
+        })
+    }
+
+    /// The first press after `bound` where the gate sends a low pulse. By then every counter is
+    /// in its cycle, so each input only allows it on presses with certain remainders.
+    fn first_low_after(
+        &self,
+        counters: &[Counter],
+        bound: u64,
+    ) -> Result<Option<u64>, Unsupported> {
+        let moduli = self
+            .inputs
+            .iter()
+            .map(|(counter, _)| counters[*counter].cycle.period as u64)
+            .collect::<Vec<_>>();
+
+        let depths = self.depths(counters);
+        let mut best: Option<u64> = None;
+        for i in 0..self.inputs.len() {
+            for &depth in &depths {
+                let remainders = self
+                    .inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(k, (counter, _))| {
+                        let cycle = counters[*counter].cycle;
+                        let (prefix, period) = (cycle.prefix as u64, cycle.period as u64);
+                        (prefix + 1..=prefix + period)
+                            .filter(|press| self.allows(counters, k, i, depth, *press))
+                            .map(|press| press % period)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+                if let Some(press) = first_common_press(&remainders, &moduli, bound)? {
+                    best = Some(best.map_or(press, |best| best.min(press)));
+                }
+            }
+        }
+
+        Ok(best)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RxAnalysis {
+    pub counters: Vec<Counter>,
+    pub gates: Vec<Gate>,
+    /// `None` if `rx` never gets a low pulse.
+    pub first_low: Option<u64>,
+}
+
+impl Network<'_> {
+    pub fn analyze_rx(&self) -> Result<RxAnalysis, Unsupported> {
+        let name = |id: ModuleId| self.module(id).name;
+
+        let Some(rx) = self.id("rx") else {
+            return unsupported("there's no rx module".to_string());
+        };
+        let Some(broadcaster) = self.id("broadcaster") else {
+            return unsupported("there's no broadcaster".to_string());
+        };
+
+        let entries = self
+            .module(broadcaster)
+            .outputs
+            .iter()
+            .map(|wire| wire.to)
+            .collect::<Vec<_>>();
+        if let Some(entry) = entries
+            .iter()
+            .find(|e| entries.iter().filter(|o| o == e).count() > 1)
+        {
+            return unsupported(format!("the broadcaster sends to '{}' twice", name(*entry)));
+        }
+
+        // Which of the broadcaster's outputs each module can be reached from, not counting rx
+        let mut owners: HashMap<ModuleId, Vec<usize>> = HashMap::new();
+        for (index, &entry) in entries.iter().enumerate().filter(|(_, e)| **e != rx) {
+            let mut seen = HashSet::from([entry]);
+            let mut stack = vec![entry];
+            while let Some(id) = stack.pop() {
+                owners.entry(id).or_default().push(index);
+                for wire in &self.module(id).outputs {
+                    if wire.to == broadcaster {
+                        return unsupported(format!("'{}' sends to the broadcaster", name(id)));
+                    }
+                    if wire.to != rx && seen.insert(wire.to) {
+                        stack.push(wire.to);
+                    }
+                }
+            }
+        }
+
+        // Only gates can be reached from more than one output
+        let mut gates = vec![];
+        for (&id, owners) in &owners {
+            if owners.len() == 1 {
+                continue;
+            }
+
+            let module = self.module(id);
+            if module.downcast::<Conjunction>().is_none()
+                || module.outputs.iter().any(|wire| wire.to != rx)
+            {
+                return unsupported(format!(
+                    "'{}' can be reached from both '{}' and '{}'",
+                    name(id),
+                    name(entries[owners[0]]),
+                    name(entries[owners[1]])
+                ));
+            }
+            gates.push(id);
+        }
+        gates.sort();
+
+        // Counters that never send anything out of them don't matter, so they're left out
+        let mut counters = vec![];
+        let mut counter_index = HashMap::new();
+        for (index, &entry) in entries.iter().enumerate() {
+            let mut modules = owners
+                .iter()
+                .filter(|(_, owners)| **owners == [index])
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            modules.sort();
+
+            let exits = modules
+                .iter()
+                .flat_map(|id| {
+                    self.module(*id)
+                        .outputs
+                        .iter()
+                        .map(move |wire| (*id, wire.to))
+                })
+                .filter(|(_, to)| *to == rx || gates.contains(to))
+                .collect::<Vec<_>>();
+            if exits.is_empty() {
+                continue;
+            }
+
+            counter_index.insert(index, counters.len());
+            counters.push(Counter {
+                entry,
+                modules,
+                exits,
+                cycle: Cycle {
+                    prefix: 0,
+                    period: 0,
+                },
+                fires: vec![],
+                log: vec![],
+            });
+        }
+        let counter_of = |id: &ModuleId| match owners.get(id).map(Vec::as_slice) {
+            Some([index]) => counter_index.get(index).copied(),
+            _ => None,
+        };
+
+        for &input in &self.module(rx).inputs {
+            if !gates.contains(&input) && counter_of(&input).is_none() {
+                return unsupported(format!(
+                    "'{}' sends to rx without being part of a counter or a gate",
+                    name(input)
+                ));
+            }
+        }
+
+        let gates = gates
+            .into_iter()
+            .map(|gate| {
+                let mut inputs = vec![];
+                for input in &self.module(gate).inputs {
+                    let Some(counter) = counter_of(input) else {
+                        return unsupported(format!(
+                            "'{}' feeds '{}' without being part of a counter",
+                            name(*input),
+                            name(gate)
+                        ));
+                    };
+                    if inputs.iter().any(|(other, _)| *other == counter) {
+                        return unsupported(format!(
+                            "the counter starting at '{}' feeds '{}' more than once",
+                            name(counters[counter].entry),
+                            name(gate)
+                        ));
+                    }
+
+                    let exit = counters[counter]
+                        .exits
+                        .iter()
+                        .position(|exit| *exit == (*input, gate))
+                        .unwrap();
+                    inputs.push((counter, exit));
+                }
+
+                inputs.sort();
+                Ok(Gate {
+                    module: gate,
+                    inputs,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let lows = self.find_cycles(rx, &mut counters)?;
+        let bound = lows.len() as u64;
+
+        // Any press up to the bound was simulated, so the gates have to agree with it
+        for gate in &gates {
+            let depths = gate.depths(&counters);
+            for press in 1..=bound {
+                let simulated = lows[press as usize - 1].contains(&gate.module);
+                if gate.sends_low(&counters, &depths, press) != simulated {
+                    return unsupported(format!(
+                        "'{}' doesn't behave like its counters say it should on press {press}",
+                        name(gate.module)
+                    ));
+                }
+            }
+        }
+
+        // After the bound, the counters only repeat presses that were already simulated, so only
+        // the gates combining them can send a low pulse for the first time
+        let first_low = match lows.iter().position(|lows| !lows.is_empty()) {
+            Some(press) => Some(press as u64 + 1),
+            None => gates
+                .iter()
+                .map(|gate| gate.first_low_after(&counters, bound))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .min(),
+        };
+
+        Ok(RxAnalysis {
+            counters,
+            gates,
+            first_low,
+        })
+    }
+
+    /// Presses the button until every counter is back in a state it's been in before, filling
+    /// in their cycles and logs. Returns the modules that sent `rx` a low pulse on each press.
+    fn find_cycles(
+        &self,
+        rx: ModuleId,
+        counters: &mut [Counter],
+    ) -> Result<Vec<Vec<ModuleId>>, Unsupported> {
+        let mut network = self.clone();
+        let state = |network: &Network, counter: &Counter| {
+            counter
+                .modules
+                .iter()
+                .flat_map(|id| network.module(*id).behaviour.state())
+                .collect::<Vec<_>>()
+        };
+
+        let mut levels = counters
+            .iter()
+            .map(|counter| vec![Pulse::Low; counter.exits.len()])
+            .collect::<Vec<_>>();
+        let mut seen = counters
+            .iter()
+            .zip(&levels)
+            .map(|(counter, levels)| {
+                HashMap::from([((state(&network, counter), levels.clone()), 0)])
+            })
+            .collect::<Vec<_>>();
+        let mut done = vec![false; counters.len()];
+        let mut lows = vec![];
+
+        while done.contains(&false) {
+            if network.presses() == MAX_PRESSES {
+                let counter = &counters[done.iter().position(|done| !done).unwrap()];
+                return unsupported(format!(
+                    "the counter starting at '{}' doesn't repeat within {MAX_PRESSES} presses",
+                    self.module(counter.entry).name
+                ));
+            }
+
+            let press = network.press();
+            lows.push(
+                press
+                    .events
+                    .iter()
+                    .filter(|event| event.to == rx && event.pulse == Pulse::Low)
+                    .map(|event| event.from)
+                    .collect(),
+            );
+
+            for (i, counter) in counters.iter_mut().enumerate() {
+                if done[i] {
+                    continue;
+                }
+
+                let start = levels[i].clone();
+                let mut outputs = vec![];
+                for event in &press.events {
+                    if let Some(exit) = counter
+                        .exits
+                        .iter()
+                        .position(|exit| *exit == (event.from, event.to))
+                    {
+                        levels[i][exit] = event.pulse;
+                        outputs.push(Output {
+                            exit,
+                            depth: event.depth,
+                            pulse: event.pulse,
+                        });
+                    }
+                }
+
+                if outputs.iter().any(|output| output.pulse == Pulse::High) {
+                    counter.fires.push(press.number);
+                }
+                counter.log.push(PressLog { start, outputs });
+
+                let key = (state(&network, counter), levels[i].clone());
+                if let Some(previous) = seen[i].insert(key, press.number) {
+                    counter.cycle = Cycle {
+                        prefix: previous as usize,
+                        period: (press.number - previous) as usize,
+                    };
+                    done[i] = true;
+                }
+            }
+        }
+
+        Ok(lows)
+    }
+}
+
+/// The first number after `bound` with one of the `remainders` for every modulus.
+fn first_common_press(
+    remainders: &[Vec<u64>],
+    moduli: &[u64],
+    bound: u64,
+) -> Result<Option<u64>, Unsupported> {
+    let combinations = remainders
+        .iter()
+        .try_fold(1usize, |acc, remainders| acc.checked_mul(remainders.len()));
+    match combinations {
+        Some(0) => return Ok(None),
+        Some(combinations) if combinations <= MAX_COMBINATIONS => {}
+        _ => {
+            return unsupported(format!(
+                "the counters line up on too many different presses to combine (over \
+                 {MAX_COMBINATIONS} combinations)"
+            ))
+        }
+    }
+
+    let mut best: Option<u64> = None;
+    let mut choice = vec![0; remainders.len()];
+    loop {
+        let solution = remainders.iter().zip(moduli).zip(&choice).try_fold(
+            (0, 1),
+            |(x, modulus), ((remainders, m), choice)| {
+                crt((x, modulus), (remainders[*choice] as u128, *m as u128))
+            },
+        );
+
+        if let Some((x, modulus)) = solution {
+            // The smallest press past the bound with the right remainder
+            let bound = bound as u128 + 1;
+            let press = x + (bound.saturating_sub(x)).div_ceil(modulus) * modulus;
+            if let Ok(press) = u64::try_from(press) {
+                best = Some(best.map_or(press, |best| best.min(press)));
+            }
+        }
+
+        // Next combination, like counting with a different base for each digit
+        let Some(i) = (0..choice.len()).find(|i| choice[*i] + 1 < remainders[*i].len()) else {
+            break;
+        };
+        choice[i] += 1;
+        choice[..i].fill(0);
+    }
+
+    Ok(best)
+}
+
+/// Combines `x = a (mod m)` and `x = b (mod n)` into a single congruence, if there's a solution.
+/// The moduli don't have to be coprime.
+fn crt((a, m): (u128, u128), (b, n): (u128, u128)) -> Option<(u128, u128)> {
+    let (g, p, _) = extended_gcd(m as i128, n as i128);
+    let diff = b as i128 - a as i128;
+    if diff % g != 0 {
+        return None;
+    }
+
+    let lcm = m / g as u128 * n;
+    let step = (n as i128) / g;
+    let k = (diff / g % step * p % step + step) % step;
+    Some(((a + k as u128 * m) % lcm, lcm))
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+
+    let (g, x, y) = extended_gcd(b, a % b);
+    (g, y, x - a / b * y)
+}
+
+impl RxAnalysis {
+    /// Describes every counter and gate and the answer, with modules called by their names in
+    /// `network`.
+    pub fn report(&self, network: &Network) -> String {
+        let name = |id: ModuleId| network.module(id).name;
+        let mut report = String::new();
+
+        for counter in &self.counters {
+            let exits = counter
+                .exits
+                .iter()
+                .map(|(from, to)| format!("{} -> {}", name(*from), name(*to)))
+                .collect::<Vec<_>>();
+            report += &format!(
+                "Counter from {} ({} modules, exits {}): high out of it on presses {:?}, repeats \
+                 every {} presses after the first {}\n",
+                name(counter.entry),
+                counter.modules.len(),
+                exits.join(", "),
+                counter.fires,
+                counter.cycle.period,
+                counter.cycle.prefix
+            );
+        }
+
+        for gate in &self.gates {
+            let counters = gate
+                .inputs
+                .iter()
+                .map(|(counter, _)| name(self.counters[*counter].entry))
+                .collect::<Vec<_>>();
+            report += &format!(
+                "Gate {} combines the counters from {}\n",
+                name(gate.module),
+                counters.join(", ")
+            );
+        }
+
+        report += &match self.first_low {
+            Some(press) => format!("rx first gets a low pulse on press {press}\n"),
+            None => "rx never gets a low pulse\n".to_string(),
+        };
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(network: &Network, max_presses: u64) -> Option<u64> {
+        let rx = network.id("rx").unwrap();
+        network
+            .clone()
+            .press_until(
+                |press| {
+                    press
+                        .events
+                        .iter()
+                        .any(|event| event.to == rx && event.pulse == Pulse::Low)
+                },
+                max_presses,
+            )
+            .map(|press| press.number)
+    }
+
+    #[test]
+    fn congruences() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+    }
+
+    #[test]
+    fn counters() {
+        // Two chains of flip-flops counting to 4 and 8 presses, each with a conjunction that
+        // fires once all of its flip-flops are on
+        let network = Network::parse(
+            "\
+broadcaster -> a1, b1
+%a1 -> a2, ha
+%a2 -> ha
+&ha -> ia
+&ia -> rxf
+%b1 -> b2, hb
+%b2 -> b3, hb
+%b3 -> hb
+&hb -> ib
+&ib -> rxf
+&rxf -> rx
+",
+        )
+        .unwrap();
+        let analysis = network.analyze_rx().unwrap();
+
+        // The conjunctions at the end only remember their inputs being high after the first
+        // press, so neither counter is back to its starting state
+        let cycles = analysis
+            .counters
+            .iter()
+            .map(|counter| (counter.cycle.prefix, counter.cycle.period))
+            .collect::<Vec<_>>();
+        assert_eq!(cycles, [(1, 4), (1, 8)]);
+        assert_eq!(analysis.first_low, brute_force(&network, 100));
+        assert_eq!(analysis.first_low, Some(7));
+    }
+
+    #[test]
+    fn remembered_pulses() {
+        // b2 stays high from press 2, so the gate goes off when a turns on again on press 3
+        let network =
+            Network::parse("broadcaster -> a, b\n%a -> rxf\n%b -> b2\n%b2 -> rxf\n&rxf -> rx\n")
+                .unwrap();
+        assert_eq!(network.analyze_rx().unwrap().first_low, Some(3));
+        assert_eq!(brute_force(&network, 100), Some(3));
+    }
+
+    #[test]
+    fn resetting_counters() {
+        // Counters that reset after 3 and 5 presses, like in the puzzle, so they only line up
+        // long after their first cycle
+        let network = Network::parse(
+            "\
+broadcaster -> a0, b0
+%a0 -> a1, ca
+%a1 -> ca
+&ca -> a0, ia
+&ia -> rxf
+%b0 -> b1, cb
+%b1 -> b2
+%b2 -> cb
+&cb -> b0, b1, ib
+&ib -> rxf
+&rxf -> rx
+",
+        )
+        .unwrap();
+        let analysis = network.analyze_rx().unwrap();
+        let bound = analysis
+            .counters
+            .iter()
+            .map(|counter| counter.cycle.prefix + counter.cycle.period)
+            .max()
+            .unwrap();
+
+        let answer = brute_force(&network, 1000);
+        assert!(answer.unwrap() > bound as u64);
+        assert_eq!(analysis.first_low, answer);
+    }
+
+    #[test]
+    fn structures() {
+        // Fed straight from a counter, by two gates, and by a gate with an input that only ever
+        // gets high pulses
+        for (input, first_low) in [
+            ("broadcaster -> a\n%a -> b\n%b -> rx\n", Some(4)),
+            (
+                "broadcaster -> a, b, c\n%a -> g1\n%b -> g1, g2\n%c -> c2\n%c2 -> g2\n&g1 -> rx\n&g2 -> rx\n",
+                Some(1),
+            ),
+            (
+                "broadcaster -> a, b\n&a -> c\n%c -> g\n%b -> g\n&g -> rx\n",
+                None,
+            ),
+        ] {
+            let network = Network::parse(input).unwrap();
+            assert_eq!(network.analyze_rx().unwrap().first_low, first_low);
+            assert_eq!(brute_force(&network, 1000), first_low);
+        }
+
+        let network = Network::parse("broadcaster -> a, b\n%a -> c\n%b -> c\n%c -> rx\n").unwrap();
+        assert_eq!(
+            network.analyze_rx().unwrap_err().to_string(),
+            "Can't analyze the network: 'c' can be reached from both 'a' and 'b'"
+        );
+    }
+}
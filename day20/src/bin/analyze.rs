@@ -0,0 +1,14 @@
+use day20::Network;
+
+fn main() {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+
+    let network = Network::parse(&input).unwrap();
+    match network.analyze_rx() {
+        Ok(analysis) => print!("{}", analysis.report(&network)),
+        Err(err) => eprintln!("{err}"),
+    }
+}
@@ -0,0 +1,17 @@
+use day20::Network;
+
+/// Prints the network as a Graphviz graph after pressing the button the number of times given
+/// as the first argument, 0 by default, so the flip-flops that are on stand out.
+fn main() {
+    let input = match std::env::args().nth(2) {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+    let presses = std::env::args().nth(1).map_or(0, |n| n.parse().unwrap());
+
+    let mut network = Network::parse(&input).unwrap();
+    for _ in 0..presses {
+        network.press();
+    }
+    print!("{}", network.to_dot());
+}
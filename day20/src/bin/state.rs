@@ -0,0 +1,17 @@
+use day20::Network;
+
+/// Prints what every flip-flop and conjunction remembers after pressing the button the number
+/// of times given as the first argument, 1000 by default.
+fn main() {
+    let input = match std::env::args().nth(2) {
+        Some(path) => std::fs::read_to_string(path).unwrap(),
+        None => include_str!("./input.txt").to_string(),
+    };
+    let presses = std::env::args().nth(1).map_or(1000, |n| n.parse().unwrap());
+
+    let mut network = Network::parse(&input).unwrap();
+    for _ in 0..presses {
+        network.press();
+    }
+    print!("{}", network.snapshot());
+}
@@ -0,0 +1,56 @@
+use crate::{Broadcaster, Conjunction, FlipFlop, Network};
+use std::fmt::Write;
+
+impl Network<'_> {
+    /// A Graphviz graph with a node for every module and an edge for every wire. The broadcaster
+    /// is a diamond, flip-flops are boxes, filled in while they're on, and conjunctions are
+    /// trapeziums. Anything else, like the button and `rx`, is plain text.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph network {{").unwrap();
+        writeln!(dot, "  rankdir=LR;").unwrap();
+        writeln!(dot, "  node [fontname=monospace];").unwrap();
+
+        for module in self.modules() {
+            let style = if module.downcast::<Broadcaster>().is_some() {
+                "shape=diamond".to_string()
+            } else if let Some(flip_flop) = module.downcast::<FlipFlop>() {
+                let fill = if flip_flop.is_on { "#ffbf00" } else { "white" };
+                format!("shape=box, style=filled, fillcolor=\"{fill}\"")
+            } else if module.downcast::<Conjunction>().is_some() {
+                "shape=trapezium".to_string()
+            } else {
+                "shape=plaintext".to_string()
+            };
+            writeln!(dot, "  \"{}\" [{style}];", module.name).unwrap();
+        }
+
+        for module in self.modules() {
+            for wire in &module.outputs {
+                let to = self.module(wire.to).name;
+                writeln!(dot, "  \"{}\" -> \"{to}\";", module.name).unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes() {
+        let mut network = Network::parse("broadcaster -> a\n%a -> con\n&con -> output\n").unwrap();
+        network.press();
+
+        let dot = network.to_dot();
+        assert!(dot.contains("\"broadcaster\" [shape=diamond];"));
+        assert!(dot.contains("\"a\" [shape=box, style=filled, fillcolor=\"#ffbf00\"];"));
+        assert!(dot.contains("\"con\" [shape=trapezium];"));
+        assert!(dot.contains("\"button\" [shape=plaintext];"));
+        assert!(dot.contains("\"a\" -> \"con\";"));
+    }
+}
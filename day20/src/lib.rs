@@ -0,0 +1,422 @@
+use anyhow::Context;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+pub mod analysis;
+pub mod dot;
+pub mod snapshot;
+
+pub type ModuleId = usize;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Pulse {
+    Low,
+    High,
+}
+
+/// How a module reacts to the pulses it gets.
+pub trait Behaviour: BehaviourClone + fmt::Debug {
+    /// Called for every wire connected to the module, in order, before any pulses are sent.
+    fn add_input(&mut self) {}
+
+    /// Handles a pulse coming in through the `input`th wire, returning the pulse to send to
+    /// every output, if any.
+    fn receive(&mut self, input: usize, pulse: Pulse) -> Option<Pulse>;
+
+    /// Everything the module remembers, so that networks in the same state can be recognized.
+    fn state(&self) -> Vec<bool> {
+        vec![]
+    }
+}
+
+/// Implemented for every `Behaviour` that is `Clone`, so networks can be cloned and modules
+/// downcast to see what they are.
+pub trait BehaviourClone {
+    fn clone_box(&self) -> Box<dyn Behaviour>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Behaviour + Clone + 'static> BehaviourClone for T {
+    fn clone_box(&self) -> Box<dyn Behaviour> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Clone for Box<dyn Behaviour> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Sends the pulse it gets on to every output.
+#[derive(Debug, Clone, Default)]
+pub struct Broadcaster;
+
+impl Behaviour for Broadcaster {
+    fn receive(&mut self, _: usize, pulse: Pulse) -> Option<Pulse> {
+        Some(pulse)
+    }
+}
+
+/// Ignores high pulses and flips between on and off on low ones, sending high when it turns on
+/// and low when it turns off.
+#[derive(Debug, Clone, Default)]
+pub struct FlipFlop {
+    pub is_on: bool,
+}
+
+impl Behaviour for FlipFlop {
+    fn receive(&mut self, _: usize, pulse: Pulse) -> Option<Pulse> {
+        if pulse == Pulse::High {
+            return None;
+        }
+
+        self.is_on = !self.is_on;
+        Some(if self.is_on { Pulse::High } else { Pulse::Low })
+    }
+
+    fn state(&self) -> Vec<bool> {
+        vec![self.is_on]
+    }
+}
+
+/// Remembers the last pulse from every input, sending low once they're all high and high
+/// otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct Conjunction {
+    /// Whether the last pulse from each input was high.
+    pub high: Vec<bool>,
+    high_count: usize,
+}
+
+impl Behaviour for Conjunction {
+    fn add_input(&mut self) {
+        self.high.push(false);
+    }
+
+    fn receive(&mut self, input: usize, pulse: Pulse) -> Option<Pulse> {
+        let high = pulse == Pulse::High;
+        if self.high[input] != high {
+            self.high[input] = high;
+            if high {
+                self.high_count += 1;
+            } else {
+                self.high_count -= 1;
+            }
+        }
+
+        Some(if self.high_count == self.high.len() {
+            Pulse::Low
+        } else {
+            Pulse::High
+        })
+    }
+
+    fn state(&self) -> Vec<bool> {
+        self.high.clone()
+    }
+}
+
+/// A module that never sends anything, like `rx`, or the button, which only sends when pressed.
+#[derive(Debug, Clone, Default)]
+pub struct Sink;
+
+impl Behaviour for Sink {
+    fn receive(&mut self, _: usize, _: Pulse) -> Option<Pulse> {
+        None
+    }
+}
+
+/// A connection to the `input`th input of module `to`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Wire {
+    pub to: ModuleId,
+    pub input: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Module<'a> {
+    pub name: &'a str,
+    pub behaviour: Box<dyn Behaviour>,
+    pub inputs: Vec<ModuleId>,
+    pub outputs: Vec<Wire>,
+}
+
+impl Module<'_> {
+    /// The module's behaviour, if it's a `T`.
+    pub fn downcast<T: 'static>(&self) -> Option<&T> {
+        self.behaviour.as_any().downcast_ref()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Event {
+    pub from: ModuleId,
+    pub to: ModuleId,
+    pub pulse: Pulse,
+    /// Pulses leading up to this one, starting from 0 for the button's. Every pulse is handled
+    /// after all the ones with a smaller depth.
+    pub depth: usize,
+}
+
+/// Every pulse sent after pressing the button once, in the order they were handled.
+#[derive(Debug, Clone)]
+pub struct Press {
+    /// Starts from 1 for the first press.
+    pub number: u64,
+    pub events: Vec<Event>,
+}
+
+impl Press {
+    pub fn count(&self, pulse: Pulse) -> u64 {
+        self.events
+            .iter()
+            .filter(|event| event.pulse == pulse)
+            .count() as u64
+    }
+
+    pub fn sent(&self, from: ModuleId, to: ModuleId, pulse: Pulse) -> bool {
+        self.events
+            .iter()
+            .any(|event| event.from == from && event.to == to && event.pulse == pulse)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
+    modules: Vec<Module<'a>>,
+    ids: HashMap<&'a str, ModuleId>,
+    presses: u64,
+}
+
+impl<'a> Network<'a> {
+    pub const BUTTON: ModuleId = 0;
+
+    /// A network with only the button, which is connected to `broadcaster` once it's added.
+    pub fn new() -> Self {
+        let mut network = Self {
+            modules: vec![],
+            ids: HashMap::new(),
+            presses: 0,
+        };
+        network.add_module("button", Box::new(Sink));
+        network
+    }
+
+    pub fn parse(input: &'a str) -> anyhow::Result<Self> {
+        let mut network = Self::new();
+        let mut connections = vec![];
+
+        for (i, line) in input.lines().enumerate() {
+            let (name, outputs) = line
+                .split_once(" -> ")
+                .with_context(|| format!("Invalid module on line {}", i + 1))?;
+
+            let (name, behaviour): (_, Box<dyn Behaviour>) = match name.chars().next() {
+                Some('%') => (&name[1..], Box::new(FlipFlop::default())),
+                Some('&') => (&name[1..], Box::new(Conjunction::default())),
+                _ if name == "broadcaster" => (name, Box::new(Broadcaster)),
+                _ => anyhow::bail!("Unknown module type on line {}: '{name}'", i + 1),
+            };
+
+            if network.id(name).is_some() {
+                anyhow::bail!("Module '{name}' is defined twice");
+            }
+            network.add_module(name, behaviour);
+            connections.extend(outputs.split(", ").map(|output| (name, output)));
+        }
+
+        for (from, to) in connections {
+            // Modules that are only ever sent to, like `rx`, don't get a line of their own
+            let to = match network.id(to) {
+                Some(to) => to,
+                None => network.add_module(to, Box::new(Sink)),
+            };
+            network.connect(network.id(from).unwrap(), to);
+        }
+
+        Ok(network)
+    }
+
+    pub fn add_module(&mut self, name: &'a str, behaviour: Box<dyn Behaviour>) -> ModuleId {
+        let id = self.modules.len();
+        self.modules.push(Module {
+            name,
+            behaviour,
+            inputs: vec![],
+            outputs: vec![],
+        });
+        self.ids.insert(name, id);
+
+        if name == "broadcaster" {
+            self.connect(Self::BUTTON, id);
+        }
+        id
+    }
+
+    pub fn connect(&mut self, from: ModuleId, to: ModuleId) {
+        let input = self.modules[to].inputs.len();
+        self.modules[to].inputs.push(from);
+        self.modules[to].behaviour.add_input();
+        self.modules[from].outputs.push(Wire { to, input });
+    }
+
+    pub fn id(&self, name: &str) -> Option<ModuleId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn module(&self, id: ModuleId) -> &Module<'a> {
+        &self.modules[id]
+    }
+
+    pub fn modules(&self) -> &[Module<'a>] {
+        &self.modules
+    }
+
+    /// Times the button has been pressed so far.
+    pub fn presses(&self) -> u64 {
+        self.presses
+    }
+
+    /// The state of every module, which is the same exactly when the network would behave the
+    /// same from here on.
+    pub fn state(&self) -> Vec<bool> {
+        self.modules
+            .iter()
+            .flat_map(|module| module.behaviour.state())
+            .collect()
+    }
+
+    /// Presses the button and waits for all the pulses to settle.
+    pub fn press(&mut self) -> Press {
+        self.presses += 1;
+        let mut events = vec![];
+        let mut queue = self.modules[Self::BUTTON]
+            .outputs
+            .iter()
+            .map(|wire| (*wire, Self::BUTTON, Pulse::Low, 0))
+            .collect::<VecDeque<_>>();
+
+        while let Some((wire, from, pulse, depth)) = queue.pop_front() {
+            events.push(Event {
+                from,
+                to: wire.to,
+                pulse,
+                depth,
+            });
+
+            let module = &mut self.modules[wire.to];
+            if let Some(next) = module.behaviour.receive(wire.input, pulse) {
+                queue.extend(
+                    module
+                        .outputs
+                        .iter()
+                        .map(|output| (*output, wire.to, next, depth + 1)),
+                );
+            }
+        }
+
+        Press {
+            number: self.presses,
+            events,
+        }
+    }
+
+    /// Presses the button `n` times, returning every press.
+    pub fn press_times(&mut self, n: u64) -> Vec<Press> {
+        (0..n).map(|_| self.press()).collect()
+    }
+
+    /// Keeps pressing the button until a press matches `predicate`, giving up after
+    /// `max_presses` presses.
+    pub fn press_until(
+        &mut self,
+        mut predicate: impl FnMut(&Press) -> bool,
+        max_presses: u64,
+    ) -> Option<Press> {
+        (0..max_presses)
+            .map(|_| self.press())
+            .find(|press| predicate(press))
+    }
+}
+
+impl Default for Network<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output
+";
+
+    #[test]
+    fn pulses() {
+        let mut network = Network::parse(EXAMPLE).unwrap();
+        let presses = network.press_times(1000);
+        let low = presses.iter().map(|p| p.count(Pulse::Low)).sum::<u64>();
+        let high = presses.iter().map(|p| p.count(Pulse::High)).sum::<u64>();
+        assert_eq!(low * high, 11687500);
+
+        let name = |id| network.module(id).name;
+        let first = presses[0]
+            .events
+            .iter()
+            .map(|e| format!("{} -{:?}-> {}", name(e.from), e.pulse, name(e.to)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            first,
+            [
+                "button -Low-> broadcaster",
+                "broadcaster -Low-> a",
+                "a -High-> inv",
+                "a -High-> con",
+                "inv -Low-> b",
+                "con -High-> output",
+                "b -High-> con",
+                "con -Low-> output",
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_modules() {
+        #[derive(Debug, Clone)]
+        struct Inverter;
+
+        impl Behaviour for Inverter {
+            fn receive(&mut self, _: usize, pulse: Pulse) -> Option<Pulse> {
+                Some(match pulse {
+                    Pulse::Low => Pulse::High,
+                    Pulse::High => Pulse::Low,
+                })
+            }
+        }
+
+        let mut network = Network::new();
+        let broadcaster = network.add_module("broadcaster", Box::new(Broadcaster));
+        let inverter = network.add_module("not", Box::new(Inverter));
+        let flip_flop = network.add_module("ff", Box::new(FlipFlop::default()));
+        network.connect(broadcaster, inverter);
+        network.connect(inverter, flip_flop);
+
+        // The flip-flop only ever gets high pulses, so it never turns on
+        let press = network.press_until(|press| press.sent(inverter, flip_flop, Pulse::Low), 10);
+        assert!(press.is_none());
+        assert_eq!(network.presses(), 10);
+        assert_eq!(network.state(), [false]);
+    }
+}
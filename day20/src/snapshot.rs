@@ -0,0 +1,107 @@
+use crate::{Conjunction, FlipFlop, Network, Pulse};
+use std::fmt;
+
+/// What a flip-flop or conjunction remembers at some point.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ModuleState<'a> {
+    FlipFlop {
+        name: &'a str,
+        is_on: bool,
+    },
+    /// The last pulse from every input, by input name.
+    Conjunction {
+        name: &'a str,
+        inputs: Vec<(&'a str, Pulse)>,
+    },
+}
+
+/// The state of every flip-flop and conjunction in a network, in the order they were added.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snapshot<'a> {
+    /// Button presses so far.
+    pub presses: u64,
+    pub modules: Vec<ModuleState<'a>>,
+}
+
+impl<'a> Network<'a> {
+    pub fn snapshot(&self) -> Snapshot<'a> {
+        let modules = self
+            .modules()
+            .iter()
+            .filter_map(|module| {
+                if let Some(flip_flop) = module.downcast::<FlipFlop>() {
+                    return Some(ModuleState::FlipFlop {
+                        name: module.name,
+                        is_on: flip_flop.is_on,
+                    });
+                }
+
+                let conjunction = module.downcast::<Conjunction>()?;
+                let inputs = module
+                    .inputs
+                    .iter()
+                    .zip(&conjunction.high)
+                    .map(|(input, high)| {
+                        let pulse = if *high { Pulse::High } else { Pulse::Low };
+                        (self.module(*input).name, pulse)
+                    })
+                    .collect();
+                Some(ModuleState::Conjunction {
+                    name: module.name,
+                    inputs,
+                })
+            })
+            .collect();
+
+        Snapshot {
+            presses: self.presses(),
+            modules,
+        }
+    }
+}
+
+impl fmt::Display for Snapshot<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "After {} presses:", self.presses)?;
+        for module in &self.modules {
+            match module {
+                ModuleState::FlipFlop { name, is_on } => {
+                    writeln!(f, "%{name}: {}", if *is_on { "on" } else { "off" })?
+                }
+                ModuleState::Conjunction { name, inputs } => {
+                    let inputs = inputs
+                        .iter()
+                        .map(|(input, pulse)| format!("{input}={pulse:?}"))
+                        .collect::<Vec<_>>();
+                    writeln!(f, "&{name}: {}", inputs.join(", "))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot() {
+        let mut network = Network::parse(
+            "broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output\n",
+        )
+        .unwrap();
+        network.press_times(2);
+
+        assert_eq!(
+            network.snapshot().to_string(),
+            "\
+After 2 presses:
+%a: off
+&inv: a=Low
+%b: on
+&con: a=Low, b=High
+"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Shape of a sequence that eventually repeats: the first `prefix` states are never seen again,
+/// after which the states loop every `period` steps.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The smallest number of steps that ends up in the same state as taking `n` steps.
+    pub fn reduce(&self, n: u64) -> usize {
+        let prefix = self.prefix as u64;
+        if n < prefix {
+            return n as usize;
+        }
+
+        (prefix + (n - prefix) % self.period as u64) as usize
+    }
+
+    pub fn state_after<T>(&self, start: T, mut step: impl FnMut(&T) -> T, n: u64) -> T {
+        let mut state = start;
+        for _ in 0..self.reduce(n) {
+            state = step(&state);
+        }
+        state
+    }
+}
+
+/// Brent's algorithm, which only keeps two states around at a time.
+pub fn brent<T: Clone + PartialEq>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    Cycle { prefix, period }
+}
+
+/// Floyd's tortoise and hare algorithm.
+pub fn floyd<T: Clone + PartialEq>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut period = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle { prefix, period }
+}
+
+/// Steps through the sequence once, remembering a 64-bit fingerprint of every state instead of
+/// the states themselves. Much faster than the other two when `step` is expensive, at the cost
+/// of trusting that fingerprints don't collide.
+pub fn hashed<T: Hash>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = start;
+
+    for i in 0.. {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+
+        if let Some(prefix) = seen.insert(hasher.finish(), i) {
+            return Cycle {
+                prefix,
+                period: i - prefix,
+            };
+        }
+
+        state = step(&state);
+    }
+
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detectors_agree() {
+        let step = |x: &u32| (x * x + 1) % 1000;
+        let expected = Cycle {
+            prefix: 5,
+            period: 6,
+        };
+
+        assert_eq!(brent(3, step), expected);
+        assert_eq!(floyd(3, step), expected);
+        assert_eq!(hashed(3, step), expected);
+
+        let cycle = Cycle {
+            prefix: 3,
+            period: 4,
+        };
+        assert_eq!(cycle.reduce(2), 2);
+        assert_eq!(cycle.reduce(7), 3);
+        assert_eq!(cycle.reduce(1_000_000_000), 4);
+        assert_eq!(cycle.state_after(0, |x| x + 1, 9), 5);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod cycle;
+pub mod search;
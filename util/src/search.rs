@@ -0,0 +1,226 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// How much work a search did.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Stats {
+    /// Nodes taken off the queue and had their successors looked at.
+    pub expanded: usize,
+    /// Times a node was put on the queue, including ones that were already on it with a higher
+    /// cost.
+    pub pushed: usize,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Path<N> {
+    /// Every node from the start to the goal, both included.
+    pub nodes: Vec<N>,
+    pub cost: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    pub path: Option<Path<N>>,
+    pub stats: Stats,
+}
+
+/// A monotone priority queue with a bucket for every priority, which only works when nothing
+/// is pushed with a lower priority than the last one popped. That holds for Dijkstra and for
+/// A* with a consistent heuristic, and makes pushing and popping O(1) as long as the costs stay
+/// small.
+#[derive(Debug)]
+struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    current: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    fn new() -> Self {
+        Self {
+            buckets: vec![],
+            current: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, priority: usize, item: T) {
+        debug_assert!(priority >= self.current, "Priority went down");
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        while self.buckets[self.current].is_empty() {
+            self.current += 1;
+        }
+        self.len -= 1;
+        self.buckets[self.current].pop()
+    }
+}
+
+struct Node<N> {
+    node: N,
+    parent: usize,
+    cost: u32,
+    closed: bool,
+}
+
+/// Dijkstra's algorithm using a bucket queue, for graphs with small integer weights.
+///
+/// `successors` pushes the neighbours of a node along with the cost of getting to them into the
+/// given buffer, which is reused between nodes.
+pub fn dijkstra<N, S, G>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    success: G,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    S: FnMut(&N, &mut Vec<(N, u32)>),
+    G: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| 0, success)
+}
+
+/// A* using a bucket queue. `heuristic` has to be consistent, meaning it never goes down by more
+/// than the cost of an edge, or the queue won't be able to handle it. On a grid, the Manhattan
+/// distance times the smallest weight is.
+pub fn astar<N, S, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut heuristic: H,
+    mut success: G,
+) -> Search<N>
+where
+    N: Eq + Hash + Clone,
+    S: FnMut(&N, &mut Vec<(N, u32)>),
+    H: FnMut(&N) -> u32,
+    G: FnMut(&N) -> bool,
+{
+    let mut stats = Stats::default();
+    let mut nodes: Vec<Node<N>> = vec![];
+    let mut indices = HashMap::new();
+    let mut queue = BucketQueue::new();
+
+    for start in starts {
+        if let Entry::Vacant(entry) = indices.entry(start.clone()) {
+            entry.insert(nodes.len());
+            queue.push(heuristic(&start) as usize, nodes.len());
+            nodes.push(Node {
+                node: start,
+                parent: usize::MAX,
+                cost: 0,
+                closed: false,
+            });
+            stats.pushed += 1;
+        }
+    }
+
+    let mut buffer = vec![];
+    while let Some(index) = queue.pop() {
+        if nodes[index].closed {
+            continue;
+        }
+        nodes[index].closed = true;
+
+        if success(&nodes[index].node) {
+            return Search {
+                path: Some(reconstruct(&nodes, index)),
+                stats,
+            };
+        }
+
+        stats.expanded += 1;
+        let cost = nodes[index].cost;
+        successors(&nodes[index].node, &mut buffer);
+
+        for (next, weight) in buffer.drain(..) {
+            let next_cost = cost + weight;
+            let next_index = match indices.entry(next) {
+                Entry::Vacant(entry) => {
+                    let next_index = nodes.len();
+                    let estimate = heuristic(entry.key());
+                    nodes.push(Node {
+                        node: entry.key().clone(),
+                        parent: index,
+                        cost: next_cost,
+                        closed: false,
+                    });
+                    entry.insert(next_index);
+                    queue.push((next_cost + estimate) as usize, next_index);
+                    stats.pushed += 1;
+                    continue;
+                }
+                Entry::Occupied(entry) => *entry.get(),
+            };
+
+            let node = &mut nodes[next_index];
+            if node.closed || node.cost <= next_cost {
+                continue;
+            }
+            node.cost = next_cost;
+            node.parent = index;
+            queue.push((next_cost + heuristic(&node.node)) as usize, next_index);
+            stats.pushed += 1;
+        }
+    }
+
+    Search { path: None, stats }
+}
+
+fn reconstruct<N: Clone>(nodes: &[Node<N>], mut index: usize) -> Path<N> {
+    let cost = nodes[index].cost;
+    let mut path = vec![];
+    while index != usize::MAX {
+        path.push(nodes[index].node.clone());
+        index = nodes[index].parent;
+    }
+    path.reverse();
+
+    Path { nodes: path, cost }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backends_agree() {
+        // Walking along a line where each step costs the digit of the cell being entered
+        let weights = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        let successors = |x: &usize, buffer: &mut Vec<(usize, u32)>| {
+            for next in [x.wrapping_sub(1), x + 1, x + 2] {
+                if let Some(weight) = weights.get(next) {
+                    buffer.push((next, *weight));
+                }
+            }
+        };
+
+        let dijkstra = dijkstra([0], successors, |x| *x == 9);
+        let astar = astar(
+            [0],
+            successors,
+            |x| (9 - *x as u32).div_ceil(2),
+            |x| *x == 9,
+        );
+
+        let expected = Path {
+            nodes: vec![0, 1, 3, 4, 6, 8, 9],
+            cost: 1 + 1 + 5 + 2 + 5 + 3,
+        };
+        assert_eq!(dijkstra.path, Some(expected.clone()));
+        assert_eq!(astar.path, Some(expected));
+        assert!(astar.stats.expanded <= dijkstra.stats.expanded);
+
+        assert_eq!(super::dijkstra([0], successors, |x| *x == 10).path, None);
+    }
+}